@@ -0,0 +1,207 @@
+//! Solver benchmarks over `docs/samples` and the known-hard puzzles in
+//! `benches/puzzles`, compared against `benches/baseline.json`.
+//!
+//!     cargo bench --bench solver                        # compare with the baseline
+//!     cargo bench --bench solver -- --save-baseline     # record a new baseline
+//!     cargo bench --bench solver -- heart               # only puzzles matching "heart"
+//!
+//! Node counts are deterministic, so any growth beyond `BENCH_NODE_THRESHOLD`
+//! (default 0.10, i.e. 10%) is a regression. Wall time is noisy and depends on
+//! the machine, so it only fails beyond `BENCH_TIME_THRESHOLD` (default 0.50)
+//! and a millisecond of slack. `BENCH_SAMPLES` sets the timed runs per puzzle.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use illust_logic_solver_lib::puzzle::{data::PuzzleData, file_io};
+use illust_logic_solver_lib::solver::backtrack::backtrack_solve_with_stats;
+use illust_logic_solver_lib::solver::options::SolverOptions;
+use illust_logic_solver_lib::solver::puzzle::Puzzle;
+
+/// Solutions looked for, as when the app checks uniqueness.
+const MAX_SOLUTIONS: usize = 2;
+const TIMEOUT: Duration = Duration::from_secs(60);
+const TIME_SLACK_MS: f64 = 1.0;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Measurement {
+    /// Solutions found, capped at `MAX_SOLUTIONS`.
+    solutions: usize,
+    nodes: u64,
+    median_ms: f64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Baseline {
+    puzzles: BTreeMap<String, Measurement>,
+}
+
+fn manifest_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn env_or<T: std::str::FromStr>(name: &str, default: T) -> T {
+    env::var(name)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+/// Every puzzle of a directory, named after its file.
+fn puzzles_in(dir: &Path) -> Vec<(String, PuzzleData)> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .unwrap_or_else(|e| panic!("{}: {}", dir.display(), e))
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .map(|path| {
+            let name = path.file_stem().unwrap().to_string_lossy().into_owned();
+            let puzzle = file_io::load_puzzle_file(&path)
+                .unwrap_or_else(|e| panic!("{}: {:?}", path.display(), e));
+            (name, puzzle)
+        })
+        .collect()
+}
+
+/// One warm-up run, then `samples` timed runs.
+fn measure(puzzle: &PuzzleData, samples: usize) -> Result<Measurement, String> {
+    let puzzle = Puzzle::try_from(puzzle).map_err(|errors| format!("invalid: {:?}", errors))?;
+    let unknown = puzzle.unknown_grid();
+    let run = || {
+        let started = Instant::now();
+        let (solutions, stopped, stats) = backtrack_solve_with_stats(
+            &unknown,
+            &puzzle,
+            &SolverOptions::new()
+                .max_solutions(MAX_SOLUTIONS)
+                .deadline(started + TIMEOUT),
+        );
+        if stopped.is_some() {
+            return Err(format!("timed out after {:?}", TIMEOUT));
+        }
+        let elapsed_ms = started.elapsed().as_secs_f64() * 1000.0;
+        Ok((solutions.len(), stats.nodes, elapsed_ms))
+    };
+
+    let (solutions, nodes, _) = run()?;
+    let mut times = Vec::with_capacity(samples);
+    for _ in 0..samples {
+        times.push(run()?.2);
+    }
+    times.sort_by(|a, b| a.total_cmp(b));
+    Ok(Measurement {
+        solutions,
+        nodes,
+        // Microseconds are below the noise anyway
+        median_ms: (times[times.len() / 2] * 1000.0).round() / 1000.0,
+    })
+}
+
+/// Why a measurement is worse than its baseline, if it is.
+fn regression(
+    current: &Measurement,
+    baseline: &Measurement,
+    node_threshold: f64,
+    time_threshold: f64,
+) -> Option<String> {
+    if current.solutions != baseline.solutions {
+        return Some(format!(
+            "found {} solutions, baseline {}",
+            current.solutions, baseline.solutions
+        ));
+    }
+    if current.nodes as f64 > baseline.nodes as f64 * (1.0 + node_threshold) {
+        return Some(format!(
+            "{} nodes, baseline {}",
+            current.nodes, baseline.nodes
+        ));
+    }
+    let time_limit = baseline.median_ms * (1.0 + time_threshold);
+    if current.median_ms > time_limit.max(baseline.median_ms + TIME_SLACK_MS) {
+        return Some(format!(
+            "{:.3} ms, baseline {:.3} ms",
+            current.median_ms, baseline.median_ms
+        ));
+    }
+    None
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let save = args.iter().any(|a| a == "--save-baseline");
+    // cargo passes `--bench`; anything else not starting with `--` filters by name
+    let filter = args.iter().find(|a| !a.starts_with("--"));
+    let samples = env_or("BENCH_SAMPLES", 10usize).max(1);
+    let node_threshold = env_or("BENCH_NODE_THRESHOLD", 0.10);
+    let time_threshold = env_or("BENCH_TIME_THRESHOLD", 0.50);
+
+    let baseline_path = manifest_dir().join("benches/baseline.json");
+    let mut baseline: Baseline = fs::read_to_string(&baseline_path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default();
+
+    let mut puzzles = puzzles_in(&manifest_dir().join("../docs/samples"));
+    puzzles.extend(
+        puzzles_in(&manifest_dir().join("benches/puzzles"))
+            .into_iter()
+            .map(|(name, puzzle)| (format!("hard/{}", name), puzzle)),
+    );
+
+    let mut failures = 0;
+    for (name, puzzle) in puzzles {
+        if filter.is_some_and(|f| !name.contains(f.as_str())) {
+            continue;
+        }
+        let current = match measure(&puzzle, samples) {
+            Ok(current) => current,
+            Err(e) => {
+                println!("{:<32} FAILED {}", name, e);
+                failures += 1;
+                continue;
+            }
+        };
+        let verdict = match baseline.puzzles.get(&name) {
+            _ if save => "saved".to_string(),
+            None => "no baseline".to_string(),
+            Some(base) => match regression(&current, base, node_threshold, time_threshold) {
+                Some(reason) => {
+                    failures += 1;
+                    format!("REGRESSED {}", reason)
+                }
+                None => format!(
+                    "{:+.1}% time",
+                    (current.median_ms / base.median_ms - 1.0) * 100.0
+                ),
+            },
+        };
+        println!(
+            "{:<32} {:>8} nodes {:>10.3} ms  {}",
+            name, current.nodes, current.median_ms, verdict
+        );
+        if save {
+            baseline.puzzles.insert(name, current);
+        }
+    }
+
+    if save {
+        let json = serde_json::to_string_pretty(&baseline).unwrap();
+        fs::write(&baseline_path, json + "\n").unwrap();
+        println!("baseline written to {}", baseline_path.display());
+    }
+    if failures > 0 {
+        println!("{} regression(s)", failures);
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,100 @@
+#![no_main]
+//! Arguments shaped like those the frontend sends to the solve, validate,
+//! hint, transform, solution and move-check commands, with any hints,
+//! dimensions and grids. Each goes through the library function the
+//! command wraps.
+
+use std::time::{Duration, Instant};
+
+use illust_logic_solver_lib::play::{check_player_grid, SolutionCache};
+use illust_logic_solver_lib::puzzle::data::PuzzleData;
+use illust_logic_solver_lib::puzzle::hint_generator::generate_hints;
+use illust_logic_solver_lib::puzzle::transform::Transform;
+use illust_logic_solver_lib::solver::backtrack::solve_hints;
+use illust_logic_solver_lib::solver::options::SolverOptions;
+use illust_logic_solver_lib::solver::types::{CellState, Grid, SolverSettings, GRID_SIZE_LIMIT};
+use illust_logic_solver_lib::solver::validator::validate_puzzle;
+use libfuzzer_sys::fuzz_target;
+use serde::Deserialize;
+
+/// Long enough to reach the search, short enough to keep the fuzzer moving.
+const SOLVE_TIME: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct Args {
+    width: usize,
+    height: usize,
+    row_hints: Vec<Vec<u32>>,
+    col_hints: Vec<Vec<u32>>,
+    /// A picture, or player marks: 1 filled, 2 crossed out, anything else unknown.
+    grid: Vec<Vec<u8>>,
+    solution: Option<String>,
+    max_grid_size: Option<usize>,
+    options: SolverOptions,
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(args) = serde_json::from_slice::<Args>(data) else {
+        return;
+    };
+    let (rows, cols) = (&args.row_hints, &args.col_hints);
+    let settings = SolverSettings {
+        max_grid_size: args.max_grid_size.unwrap_or(GRID_SIZE_LIMIT),
+    };
+
+    // validate_puzzle_command
+    validate_puzzle(args.width, args.height, rows, cols, &settings);
+
+    // solve_puzzle, with the caller's options capped in time; messages are
+    // in the default locale
+    let options = args.options.clone().deadline(Instant::now() + SOLVE_TIME);
+    let result = solve_hints(rows, cols, &options, &settings, Default::default());
+
+    // check_moves_command, with marks of any shape
+    let marks: Grid = args
+        .grid
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|&c| match c {
+                    1 => CellState::Filled,
+                    2 => CellState::Empty,
+                    _ => CellState::Unknown,
+                })
+                .collect()
+        })
+        .collect();
+    let deadline = Instant::now() + SOLVE_TIME;
+    let cache = SolutionCache::default();
+    let _ = check_player_grid(
+        &cache,
+        rows.clone(),
+        cols.clone(),
+        &marks,
+        &settings,
+        Some(deadline),
+    );
+
+    // generate_hints_command and transform_grid_command
+    let _ = generate_hints(&args.grid);
+    for transform in Transform::ALL {
+        transform.apply_grid(&args.grid);
+    }
+
+    // check_solution_command and transform_puzzle_command decode the stored
+    // solution at the given size
+    let puzzle = PuzzleData {
+        version: "1.1".to_string(),
+        width: args.width,
+        height: args.height,
+        row_hints: args.row_hints.clone(),
+        col_hints: args.col_hints.clone(),
+        metadata: None,
+        solution: args.solution.clone(),
+    };
+    let _ = puzzle.check_result(&result);
+    for transform in Transform::ALL {
+        let _ = transform.apply_puzzle(&puzzle);
+    }
+});
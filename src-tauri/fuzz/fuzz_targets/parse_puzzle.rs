@@ -0,0 +1,15 @@
+#![no_main]
+//! Puzzle files of any content, through every parser in `file_io`.
+
+use illust_logic_solver_lib::puzzle::file_io;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(text) = std::str::from_utf8(data) {
+        let _ = file_io::parse_puzzle(text);
+    }
+    // The same JSON embedded in another document, as in saved games
+    if let Ok(value) = serde_json::from_slice(data) {
+        let _ = file_io::puzzle_from_value(value);
+    }
+});
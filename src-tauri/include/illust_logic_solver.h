@@ -0,0 +1,231 @@
+/* The C API of the illust logic solver. Generated by cbindgen from src/ffi.rs; do not edit. */
+
+#ifndef ILLUST_LOGIC_SOLVER_H
+#define ILLUST_LOGIC_SOLVER_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/*
+ Whether a call succeeded.
+ */
+typedef enum IlsStatus {
+  ILS_STATUS_OK = 0,
+  /*
+   A required pointer was NULL or a string was not valid UTF-8.
+   */
+  ILS_STATUS_INVALID_ARGUMENT = 1,
+  /*
+   The hints or the puzzle JSON were rejected.
+   */
+  ILS_STATUS_INVALID_PUZZLE = 2,
+  /*
+   A bug in the solver.
+   */
+  ILS_STATUS_INTERNAL_ERROR = 3,
+} IlsStatus;
+
+/*
+ What a solve found.
+ */
+typedef enum IlsOutcome {
+  ILS_OUTCOME_UNIQUE_SOLUTION = 0,
+  /*
+   At least two solutions; up to `max_solutions` of them, and never fewer
+   than two, are kept.
+   */
+  ILS_OUTCOME_MULTIPLE_SOLUTIONS = 1,
+  ILS_OUTCOME_NO_SOLUTION = 2,
+  /*
+   The timeout passed before the solver could tell.
+   */
+  ILS_OUTCOME_TIMEOUT = 3,
+  /*
+   The search budget ran out before the solver could tell.
+   */
+  ILS_OUTCOME_BUDGET_EXHAUSTED = 4,
+} IlsOutcome;
+
+/*
+ A validated puzzle.
+ */
+typedef struct IlsPuzzle IlsPuzzle;
+
+/*
+ The result of `ils_solve`.
+ */
+typedef struct IlsResult IlsResult;
+
+/*
+ How a solve runs. Start from `ils_solve_options_default` and change the
+ fields you need, so that code keeps working when fields are added.
+ */
+typedef struct IlsSolveOptions {
+  /*
+   Solutions to keep. At least 2 are looked for whatever this says, so
+   that uniqueness is always known.
+   */
+  size_t max_solutions;
+  /*
+   Give up after this many seconds; infinity for no limit.
+   */
+  double timeout_seconds;
+  /*
+   Search nodes to visit at most; `UINT64_MAX` for no limit.
+   */
+  uint64_t max_nodes;
+  /*
+   Rows and columns to solve at most; `UINT64_MAX` for no limit.
+   */
+  uint64_t max_line_solver_calls;
+} IlsSolveOptions;
+
+/*
+ How the answer was reached.
+ */
+typedef struct IlsSolveStats {
+  double elapsed_seconds;
+  uint64_t nodes;
+  uint32_t max_depth;
+  uint64_t line_solver_calls;
+  uint64_t cells_by_logic;
+  uint64_t cells_by_search;
+} IlsSolveStats;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/*
+ The library version, e.g. "0.1.1". The string is static.
+ */
+const char *ils_version(void);
+
+/*
+ The message of the latest failed call on this thread, or NULL if none
+ failed yet. The string stays valid until the next failure on this thread.
+ */
+const char *ils_last_error(void);
+
+/*
+ Build a puzzle from its hints.
+
+ `row_lengths` holds `height` counts, the number of hints of each row, and
+ `row_hints` all row hints one row after another; the columns likewise
+ with `width` counts. A hint array may be NULL when it holds no hints.
+
+ # Safety
+ The arrays must be as long as described above, and `out` must be valid
+ for writes.
+ */
+enum IlsStatus ils_puzzle_new(size_t width,
+                              size_t height,
+                              const uint32_t *row_hints,
+                              const size_t *row_lengths,
+                              const uint32_t *col_hints,
+                              const size_t *col_lengths,
+                              struct IlsPuzzle **out);
+
+/*
+ Build a puzzle from the contents of a puzzle file of any supported version.
+
+ # Safety
+ `json` must be a NUL-terminated string and `out` must be valid for writes.
+ */
+enum IlsStatus ils_puzzle_from_json(const char *json, struct IlsPuzzle **out);
+
+/*
+ # Safety
+ `puzzle` must come from `ils_puzzle_new` or `ils_puzzle_from_json`.
+ */
+size_t ils_puzzle_width(const struct IlsPuzzle *puzzle);
+
+/*
+ # Safety
+ `puzzle` must come from `ils_puzzle_new` or `ils_puzzle_from_json`.
+ */
+size_t ils_puzzle_height(const struct IlsPuzzle *puzzle);
+
+/*
+ # Safety
+ `puzzle` must be NULL or come from `ils_puzzle_new` or
+ `ils_puzzle_from_json`, and not be used afterwards.
+ */
+void ils_puzzle_free(struct IlsPuzzle *puzzle);
+
+/*
+ Two solutions, no timeout and no search budget.
+ */
+struct IlsSolveOptions ils_solve_options_default(void);
+
+/*
+ Solve a puzzle. `options` may be NULL for the defaults.
+
+ # Safety
+ `puzzle` must come from `ils_puzzle_new` or `ils_puzzle_from_json`,
+ `options` must be NULL or valid for reads, and `out` valid for writes.
+ */
+enum IlsStatus ils_solve(const struct IlsPuzzle *puzzle,
+                         const struct IlsSolveOptions *options,
+                         struct IlsResult **out);
+
+/*
+ # Safety
+ `result` must come from `ils_solve`.
+ */
+enum IlsOutcome ils_result_outcome(const struct IlsResult *result);
+
+/*
+ Number of solutions kept: 1 for a unique solution, 0 if none was found.
+
+ # Safety
+ `result` must come from `ils_solve`.
+ */
+size_t ils_result_solution_count(const struct IlsResult *result);
+
+/*
+ The solution at `index`: width × height bytes row by row, 1 for a filled
+ cell and 0 for an empty one. NULL if `index` is out of range. The bytes
+ stay valid until the result is freed.
+
+ # Safety
+ `result` must come from `ils_solve`.
+ */
+const uint8_t *ils_result_solution(const struct IlsResult *result, size_t index);
+
+/*
+ # Safety
+ `result` must come from `ils_solve`.
+ */
+struct IlsSolveStats ils_result_stats(const struct IlsResult *result);
+
+/*
+ The result as the JSON the app uses, e.g. `{"type":"UniqueSolution",...}`.
+ Release it with `ils_string_free`.
+
+ # Safety
+ `result` must come from `ils_solve`.
+ */
+char *ils_result_to_json(const struct IlsResult *result);
+
+/*
+ # Safety
+ `result` must be NULL or come from `ils_solve`, and not be used afterwards.
+ */
+void ils_result_free(struct IlsResult *result);
+
+/*
+ # Safety
+ `string` must be NULL or a string this library returned for freeing,
+ and not be used afterwards.
+ */
+void ils_string_free(char *string);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* ILLUST_LOGIC_SOLVER_H */
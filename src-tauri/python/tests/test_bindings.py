@@ -0,0 +1,65 @@
+import pytest
+
+import illust_logic_solver as solver
+
+CROSS = [[1], [3], [1]]
+
+
+def test_solve_unique():
+    result = solver.solve(CROSS, CROSS)
+    assert result["type"] == "UniqueSolution"
+    assert result["grid"] == [[0, 1, 0], [1, 1, 1], [0, 1, 0]]
+    assert result["stats"]["nodes"] == 1
+
+
+def test_solve_limits():
+    diagonal = [[1], [1]]
+    assert solver.solve(diagonal, diagonal)["type"] == "MultipleSolutions"
+    assert solver.solve(CROSS, CROSS, max_nodes=0)["type"] == "BudgetExhausted"
+
+
+def test_count_solutions():
+    ones = [[1]] * 4
+    assert solver.count_solutions(ones, ones) == 24
+    assert solver.count_solutions(ones, ones, limit=3) == 3
+
+
+def test_invalid_hints_raise():
+    with pytest.raises(ValueError, match="width is 1"):
+        solver.solve([[4]], [[1]], locale="en")
+    with pytest.raises(ValueError, match="unknown locale"):
+        solver.solve(CROSS, CROSS, locale="fr")
+
+
+def test_generate_hints_and_validate():
+    rows, cols = solver.generate_hints([[0, 1, 0], [1, 1, 1], [0, 1, 0]])
+    assert (rows, cols) == (CROSS, CROSS)
+    assert solver.validate(3, 3, rows, cols)["valid"]
+    with pytest.raises(ValueError):
+        solver.generate_hints([[1] * 1001])
+
+    result = solver.validate(3, 3, [[5], [1], [1]], CROSS, locale="en")
+    assert [e["type"] for e in result["errors"]] == ["HintTooLong", "SumMismatch"]
+    assert len(result["messages"]) == 2
+
+
+def test_save_and_load(tmp_path):
+    path = tmp_path / "cross.json"
+    puzzle = {
+        "version": "1.0",
+        "width": 3,
+        "height": 3,
+        "rowHints": CROSS,
+        "colHints": CROSS,
+        "metadata": {"title": "Cross"},
+    }
+    solver.save_puzzle(path, puzzle)
+    loaded = solver.load_puzzle(path)
+    assert loaded["version"] == "1.1"
+    assert loaded["rowHints"] == CROSS
+    assert loaded["metadata"]["title"] == "Cross"
+
+    with pytest.raises(ValueError):
+        solver.save_puzzle(path, {**puzzle, "width": 2})
+    with pytest.raises(OSError):
+        solver.load_puzzle(tmp_path / "missing.json")
@@ -0,0 +1,5 @@
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    illust_logic_solver_lib::server::run_cli(std::env::args().skip(1))
+}
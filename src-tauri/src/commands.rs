@@ -1,61 +1,170 @@
 use std::time::{Duration, Instant};
 
-use crate::puzzle::{data::PuzzleData, file_io, hint_generator};
+use tauri::State;
+
+use crate::game::{GameStore, GameSummary, SavedGame, AUTOSAVE_ID};
+use crate::i18n::{Locale, Localize};
+use crate::library::{
+    AddedPuzzle, Duplicate, ImportReport, LibraryEntry, LibraryPage, LibraryQuery, LibraryState,
+    SolveStatus,
+};
+use crate::play::{self, MoveCheck, SolutionCache};
+use crate::puzzle::{
+    data::PuzzleData,
+    file_io::{self, FileError},
+    fingerprint::exact_hash,
+    hint_generator,
+    solution::SolutionCheck,
+    transform::Transform,
+};
+use crate::settings::{AppSettings, SettingsState};
+use crate::stats::{self, GroupBy, SolveKind, SolveOutcome, SolveRecord, StatsGroup, StatsLog};
 use crate::solver::{
-    backtrack::backtrack_solve,
-    types::{CellState, Grid, SolveResult, ValidationResult},
+    backtrack::solve_hints,
+    options::SolverOptions,
+    puzzle::Puzzle,
+    types::{
+        Grid, SearchBudget, SolveResult, SolverSettings, ValidationError, ValidationResult,
+    },
     validator::validate_puzzle,
 };
+use crate::unwind;
+
+type Hints = Vec<Vec<u32>>;
+
+/// Failures of the command layer itself rather than of the work it was given.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandError {
+    /// A bug surfaced as a panic; carries the panic message.
+    Internal(String),
+}
+
+/// Run a command body, turning a panic into an error message. Tauri would
+/// otherwise leave the frontend with an opaque failure.
+fn guarded<T>(locale: Locale, body: impl FnOnce() -> Result<T, String>) -> Result<T, String> {
+    unwind::catch(body)
+        .unwrap_or_else(|message| Err(CommandError::Internal(message).localize(locale)))
+}
 
-fn grid_to_u8(grid: &Grid) -> Vec<Vec<u8>> {
-    grid.iter()
-        .map(|row| {
-            row.iter()
-                .map(|c| match c {
-                    CellState::Filled => 1,
-                    _ => 0,
-                })
-                .collect()
-        })
-        .collect()
+/// Every message, one per line, for errors the frontend shows together.
+fn localize_all(errors: &[ValidationError], locale: Locale) -> String {
+    let messages: Vec<String> = errors.iter().map(|e| e.localize(locale)).collect();
+    messages.join("\n")
 }
 
+/// Validate the hints, solve and record the run in the statistics log.
+/// `difficulty` is only used to group the statistics.
+fn run_solve(
+    row_hints: &[Vec<u32>],
+    col_hints: &[Vec<u32>],
+    options: &SolverOptions,
+    difficulty: Option<String>,
+    locale: Locale,
+    solver_settings: &SolverSettings,
+    log: &StatsLog,
+) -> SolveResult {
+    let result = solve_hints(row_hints, col_hints, options, solver_settings, locale);
+    let outcome = match &result {
+        SolveResult::UniqueSolution { .. } => SolveOutcome::UniqueSolution,
+        SolveResult::MultipleSolutions { .. } => SolveOutcome::MultipleSolutions,
+        SolveResult::NoSolution { .. } => SolveOutcome::NoSolution,
+        SolveResult::Timeout { .. } => SolveOutcome::Timeout,
+        SolveResult::BudgetExhausted { .. } => SolveOutcome::BudgetExhausted,
+        SolveResult::InvalidInput { .. } => return result,
+    };
+    let stats = result.stats();
+
+    // Statistics are best effort; a failed write must not hide the result
+    let _ = log.append(&SolveRecord::new(
+        row_hints,
+        col_hints,
+        difficulty,
+        SolveKind::Auto,
+        outcome,
+        (stats.elapsed_seconds * 1000.0) as u64,
+        stats.nodes,
+    ));
+    result
+}
+
+/// Solve a puzzle and record the run in the statistics log.
+/// `difficulty` is only used to group the statistics.
+/// Malformed hints come back as `SolveResult::InvalidInput` without solving.
+/// `budget` caps the search in addition to the timeout.
+// Each argument is a key of the frontend's invoke payload
+#[allow(clippy::too_many_arguments)]
 #[tauri::command]
 pub fn solve_puzzle(
     row_hints: Vec<Vec<u32>>,
     col_hints: Vec<Vec<u32>>,
     timeout_seconds: Option<u64>,
-) -> SolveResult {
-    let timeout = timeout_seconds.unwrap_or(60);
-    let deadline = Instant::now() + Duration::from_secs(timeout);
-
-    let height = row_hints.len();
-    let width = col_hints.len();
-    let grid: Grid = vec![vec![CellState::Unknown; width]; height];
-
-    let (solutions, timed_out) =
-        backtrack_solve(&grid, &row_hints, &col_hints, 2, Some(deadline));
-
-    if timed_out && solutions.is_empty() {
-        return SolveResult::Timeout {
-            elapsed_seconds: timeout as f64,
-        };
-    }
+    budget: Option<SearchBudget>,
+    difficulty: Option<String>,
+    locale: Option<Locale>,
+    settings: State<'_, SettingsState>,
+    log: State<'_, StatsLog>,
+) -> Result<SolveResult, String> {
+    let stored = settings.get();
+    let locale = locale.unwrap_or(stored.locale);
+    guarded(locale, || {
+        let timeout = timeout_seconds.unwrap_or(stored.timeout_seconds);
+        let options = SolverOptions::new()
+            .timeout(Duration::from_secs(timeout))
+            .budget(budget.unwrap_or_default());
+        Ok(run_solve(
+            &row_hints,
+            &col_hints,
+            &options,
+            difficulty,
+            locale,
+            &stored.solver_settings(),
+            &log,
+        ))
+    })
+}
 
-    match solutions.len() {
-        0 => SolveResult::NoSolution,
-        1 => SolveResult::UniqueSolution {
-            grid: grid_to_u8(&solutions[0]),
-        },
-        _ => SolveResult::MultipleSolutions {
-            grids: solutions.iter().map(|s| grid_to_u8(s)).collect(),
-        },
-    }
+/// Solve with options passed through from the frontend as they are stored.
+/// Without a timeout the one in the settings applies.
+#[tauri::command]
+pub fn solve_command(
+    row_hints: Vec<Vec<u32>>,
+    col_hints: Vec<Vec<u32>>,
+    options: Option<SolverOptions>,
+    difficulty: Option<String>,
+    locale: Option<Locale>,
+    settings: State<'_, SettingsState>,
+    log: State<'_, StatsLog>,
+) -> Result<SolveResult, String> {
+    let stored = settings.get();
+    let locale = locale.unwrap_or(stored.locale);
+    guarded(locale, || {
+        let mut options = options.unwrap_or_default();
+        if options.timeout_seconds.is_none() {
+            options.timeout_seconds = Some(stored.timeout_seconds as f64);
+        }
+        Ok(run_solve(
+            &row_hints,
+            &col_hints,
+            &options,
+            difficulty,
+            locale,
+            &stored.solver_settings(),
+            &log,
+        ))
+    })
 }
 
+/// Rows shorter than the longest are read as ending in empty cells.
 #[tauri::command]
-pub fn generate_hints_command(grid: Vec<Vec<u8>>) -> (Vec<Vec<u32>>, Vec<Vec<u32>>) {
-    hint_generator::generate_hints(&grid)
+pub fn generate_hints_command(
+    grid: Vec<Vec<u8>>,
+    locale: Option<Locale>,
+    settings: State<'_, SettingsState>,
+) -> Result<(Hints, Hints), String> {
+    let locale = locale.unwrap_or_else(|| settings.get().locale);
+    guarded(locale, || {
+        hint_generator::generate_hints(&grid).map_err(|errors| localize_all(&errors, locale))
+    })
 }
 
 #[tauri::command]
@@ -64,16 +173,480 @@ pub fn validate_puzzle_command(
     height: usize,
     row_hints: Vec<Vec<u32>>,
     col_hints: Vec<Vec<u32>>,
-) -> ValidationResult {
-    validate_puzzle(width, height, &row_hints, &col_hints)
+    solver_settings: Option<SolverSettings>,
+    locale: Option<Locale>,
+    settings: State<'_, SettingsState>,
+) -> Result<ValidationResult, String> {
+    let stored = settings.get();
+    let locale = locale.unwrap_or(stored.locale);
+    let solver_settings = solver_settings.unwrap_or_else(|| stored.solver_settings());
+    guarded(locale, || {
+        Ok(
+            validate_puzzle(width, height, &row_hints, &col_hints, &solver_settings)
+                .localized(locale),
+        )
+    })
+}
+
+#[tauri::command]
+pub fn solve_status_message_command(
+    result: SolveResult,
+    locale: Option<Locale>,
+    settings: State<'_, SettingsState>,
+) -> Result<String, String> {
+    let locale = locale.unwrap_or_else(|| settings.get().locale);
+    guarded(locale, || Ok(result.localize(locale)))
+}
+
+#[tauri::command]
+pub fn load_puzzle_file_command(
+    path: String,
+    locale: Option<Locale>,
+    settings: State<'_, SettingsState>,
+) -> Result<PuzzleData, String> {
+    let locale = locale.unwrap_or_else(|| settings.get().locale);
+    guarded(locale, || {
+        file_io::load_puzzle_file(std::path::Path::new(&path)).map_err(|e| e.localize(locale))
+    })
+}
+
+/// Save a puzzle; `solution` is the authored picture to store with it, if any.
+#[tauri::command]
+pub fn save_puzzle_file_command(
+    path: String,
+    mut puzzle_data: PuzzleData,
+    solution: Option<Vec<Vec<u8>>>,
+    locale: Option<Locale>,
+    settings: State<'_, SettingsState>,
+) -> Result<(), String> {
+    let locale = locale.unwrap_or_else(|| settings.get().locale);
+    guarded(locale, || {
+        if let Some(grid) = solution {
+            puzzle_data.set_solution(&grid);
+        }
+        file_io::save_puzzle_file(std::path::Path::new(&path), &puzzle_data)
+            .map_err(|e| e.localize(locale))
+    })
+}
+
+/// Compare a solve result with the solution stored in the puzzle.
+#[tauri::command]
+pub fn check_solution_command(
+    puzzle_data: PuzzleData,
+    result: SolveResult,
+    locale: Option<Locale>,
+    settings: State<'_, SettingsState>,
+) -> Result<SolutionCheck, String> {
+    let locale = locale.unwrap_or_else(|| settings.get().locale);
+    guarded(locale, || {
+        puzzle_data
+            .check_result(&result)
+            .map_err(|e| FileError::InvalidSolution(e).localize(locale))
+    })
+}
+
+/// Flip, rotate or transpose a puzzle, its hints and its stored solution.
+#[tauri::command]
+pub fn transform_puzzle_command(
+    puzzle_data: PuzzleData,
+    transform: Transform,
+    locale: Option<Locale>,
+    settings: State<'_, SettingsState>,
+) -> Result<PuzzleData, String> {
+    let locale = locale.unwrap_or_else(|| settings.get().locale);
+    guarded(locale, || {
+        transform
+            .apply_puzzle(&puzzle_data)
+            .map_err(|e| e.localize(locale))
+    })
+}
+
+/// Transform a 0/1 picture, such as an authored solution.
+#[tauri::command]
+pub fn transform_grid_command(
+    grid: Vec<Vec<u8>>,
+    transform: Transform,
+    locale: Option<Locale>,
+    settings: State<'_, SettingsState>,
+) -> Result<Vec<Vec<u8>>, String> {
+    let locale = locale.unwrap_or_else(|| settings.get().locale);
+    guarded(locale, || Ok(transform.apply_grid(&grid)))
+}
+
+/// Transform the pictures of a solve result.
+#[tauri::command]
+pub fn transform_solve_result_command(
+    result: SolveResult,
+    transform: Transform,
+    locale: Option<Locale>,
+    settings: State<'_, SettingsState>,
+) -> Result<SolveResult, String> {
+    let locale = locale.unwrap_or_else(|| settings.get().locale);
+    guarded(locale, || Ok(transform.apply_result(&result)))
+}
+
+#[tauri::command]
+pub fn get_settings_command(settings: State<'_, SettingsState>) -> Result<AppSettings, String> {
+    let stored = settings.get();
+    let locale = stored.locale;
+    guarded(locale, || Ok(stored))
+}
+
+#[tauri::command]
+pub fn update_settings_command(
+    new_settings: AppSettings,
+    settings: State<'_, SettingsState>,
+) -> Result<AppSettings, String> {
+    let locale = new_settings.locale;
+    guarded(locale, || {
+        settings.update(new_settings).map_err(|e| e.localize(locale))
+    })
+}
+
+#[tauri::command]
+pub fn reset_settings_command(settings: State<'_, SettingsState>) -> Result<AppSettings, String> {
+    let locale = settings.get().locale;
+    guarded(locale, || {
+        settings
+            .update(AppSettings::default())
+            .map_err(|e| e.localize(locale))
+    })
+}
+
+#[tauri::command]
+pub fn library_search_command(
+    query: Option<LibraryQuery>,
+    locale: Option<Locale>,
+    library: State<'_, LibraryState>,
+    settings: State<'_, SettingsState>,
+) -> Result<LibraryPage, String> {
+    let locale = locale.unwrap_or_else(|| settings.get().locale);
+    guarded(locale, || {
+        library
+            .with(|l| Ok(l.search(&query.unwrap_or_default())))
+            .map_err(|e| e.localize(locale))
+    })
+}
+
+#[tauri::command]
+pub fn library_tags_command(
+    locale: Option<Locale>,
+    library: State<'_, LibraryState>,
+    settings: State<'_, SettingsState>,
+) -> Result<Vec<String>, String> {
+    let locale = locale.unwrap_or_else(|| settings.get().locale);
+    guarded(locale, || {
+        library.with(|l| Ok(l.tags())).map_err(|e| e.localize(locale))
+    })
+}
+
+#[tauri::command]
+pub fn library_load_command(
+    id: String,
+    locale: Option<Locale>,
+    library: State<'_, LibraryState>,
+    settings: State<'_, SettingsState>,
+) -> Result<PuzzleData, String> {
+    let locale = locale.unwrap_or_else(|| settings.get().locale);
+    guarded(locale, || {
+        library.with(|l| l.load(&id)).map_err(|e| e.localize(locale))
+    })
+}
+
+/// Copy a puzzle into the library; `solution` is stored with it as on save.
+/// Puzzles it duplicates come back as a warning.
+#[tauri::command]
+pub fn library_add_command(
+    mut puzzle_data: PuzzleData,
+    solution: Option<Vec<Vec<u8>>>,
+    tags: Option<Vec<String>>,
+    locale: Option<Locale>,
+    library: State<'_, LibraryState>,
+    settings: State<'_, SettingsState>,
+) -> Result<AddedPuzzle, String> {
+    let locale = locale.unwrap_or_else(|| settings.get().locale);
+    guarded(locale, || {
+        if let Some(grid) = solution {
+            puzzle_data.set_solution(&grid);
+        }
+        library
+            .with(|l| l.add(&puzzle_data, tags.unwrap_or_default()))
+            .map_err(|e| e.localize(locale))
+    })
+}
+
+/// Library puzzles with the same hints as `puzzle_data`, e.g. before importing it.
+#[tauri::command]
+pub fn library_duplicates_command(
+    puzzle_data: PuzzleData,
+    locale: Option<Locale>,
+    library: State<'_, LibraryState>,
+    settings: State<'_, SettingsState>,
+) -> Result<Vec<Duplicate>, String> {
+    let locale = locale.unwrap_or_else(|| settings.get().locale);
+    guarded(locale, || {
+        library
+            .with(|l| Ok(l.duplicates_of(&puzzle_data)))
+            .map_err(|e| e.localize(locale))
+    })
+}
+
+#[tauri::command]
+pub fn library_import_command(
+    paths: Vec<String>,
+    tags: Option<Vec<String>>,
+    skip_duplicates: bool,
+    locale: Option<Locale>,
+    library: State<'_, LibraryState>,
+    settings: State<'_, SettingsState>,
+) -> Result<ImportReport, String> {
+    let locale = locale.unwrap_or_else(|| settings.get().locale);
+    guarded(locale, || {
+        let paths: Vec<std::path::PathBuf> = paths.into_iter().map(Into::into).collect();
+        library
+            .with(|l| l.import(&paths, &tags.unwrap_or_default(), skip_duplicates, locale))
+            .map_err(|e| e.localize(locale))
+    })
+}
+
+#[tauri::command]
+pub fn library_set_status_command(
+    id: String,
+    status: SolveStatus,
+    locale: Option<Locale>,
+    library: State<'_, LibraryState>,
+    settings: State<'_, SettingsState>,
+) -> Result<LibraryEntry, String> {
+    let locale = locale.unwrap_or_else(|| settings.get().locale);
+    guarded(locale, || {
+        library
+            .with(|l| l.set_status(&id, status))
+            .map_err(|e| e.localize(locale))
+    })
+}
+
+#[tauri::command]
+pub fn library_set_tags_command(
+    id: String,
+    tags: Vec<String>,
+    locale: Option<Locale>,
+    library: State<'_, LibraryState>,
+    settings: State<'_, SettingsState>,
+) -> Result<LibraryEntry, String> {
+    let locale = locale.unwrap_or_else(|| settings.get().locale);
+    guarded(locale, || {
+        library
+            .with(|l| l.set_tags(&id, tags))
+            .map_err(|e| e.localize(locale))
+    })
+}
+
+#[tauri::command]
+pub fn library_remove_command(
+    id: String,
+    locale: Option<Locale>,
+    library: State<'_, LibraryState>,
+    settings: State<'_, SettingsState>,
+) -> Result<(), String> {
+    let locale = locale.unwrap_or_else(|| settings.get().locale);
+    guarded(locale, || {
+        library.with(|l| l.remove(&id)).map_err(|e| e.localize(locale))
+    })
+}
+
+/// Save a game under `id`, or under a new id when none is given.
+#[tauri::command]
+pub fn save_game_command(
+    game: SavedGame,
+    id: Option<String>,
+    locale: Option<Locale>,
+    games: State<'_, GameStore>,
+    settings: State<'_, SettingsState>,
+) -> Result<GameSummary, String> {
+    let locale = locale.unwrap_or_else(|| settings.get().locale);
+    guarded(locale, || {
+        games
+            .save(id.as_deref(), &game)
+            .map_err(|e| e.localize(locale))
+    })
+}
+
+/// Overwrite the single autosave slot.
+#[tauri::command]
+pub fn autosave_game_command(
+    game: SavedGame,
+    locale: Option<Locale>,
+    games: State<'_, GameStore>,
+    settings: State<'_, SettingsState>,
+) -> Result<GameSummary, String> {
+    let locale = locale.unwrap_or_else(|| settings.get().locale);
+    guarded(locale, || {
+        games
+            .save(Some(AUTOSAVE_ID), &game)
+            .map_err(|e| e.localize(locale))
+    })
+}
+
+#[tauri::command]
+pub fn list_games_command(
+    locale: Option<Locale>,
+    games: State<'_, GameStore>,
+    settings: State<'_, SettingsState>,
+) -> Result<Vec<GameSummary>, String> {
+    let locale = locale.unwrap_or_else(|| settings.get().locale);
+    guarded(locale, || games.list().map_err(|e| e.localize(locale)))
+}
+
+#[tauri::command]
+pub fn load_game_command(
+    id: String,
+    locale: Option<Locale>,
+    games: State<'_, GameStore>,
+    settings: State<'_, SettingsState>,
+) -> Result<SavedGame, String> {
+    let locale = locale.unwrap_or_else(|| settings.get().locale);
+    guarded(locale, || games.load(&id).map_err(|e| e.localize(locale)))
+}
+
+#[tauri::command]
+pub fn delete_game_command(
+    id: String,
+    locale: Option<Locale>,
+    games: State<'_, GameStore>,
+    settings: State<'_, SettingsState>,
+) -> Result<(), String> {
+    let locale = locale.unwrap_or_else(|| settings.get().locale);
+    guarded(locale, || games.remove(&id).map_err(|e| e.localize(locale)))
+}
+
+/// Check a player's grid without revealing the answer.
+/// The puzzle is solved once and cached; the timeout applies to that solve.
+#[tauri::command]
+pub fn check_moves_command(
+    row_hints: Vec<Vec<u32>>,
+    col_hints: Vec<Vec<u32>>,
+    grid: Grid,
+    locale: Option<Locale>,
+    cache: State<'_, SolutionCache>,
+    settings: State<'_, SettingsState>,
+) -> Result<MoveCheck, String> {
+    let stored = settings.get();
+    let locale = locale.unwrap_or(stored.locale);
+    guarded(locale, || {
+        let deadline = Instant::now() + Duration::from_secs(stored.timeout_seconds);
+        play::check_player_grid(
+            &cache,
+            row_hints,
+            col_hints,
+            &grid,
+            &stored.solver_settings(),
+            Some(deadline),
+        )
+        .map_err(|e| e.localize(locale))
+    })
+}
+
+/// Record the end of a play session; `solved` is false when the player gave up.
+/// The hints are checked as `check_moves_command` checks them, so a session
+/// is only recorded for a puzzle that could have been played.
+#[tauri::command]
+pub fn record_play_command(
+    puzzle_data: PuzzleData,
+    solved: bool,
+    elapsed_ms: u64,
+    locale: Option<Locale>,
+    stats: State<'_, StatsLog>,
+    settings: State<'_, SettingsState>,
+) -> Result<(), String> {
+    let stored = settings.get();
+    let locale = locale.unwrap_or(stored.locale);
+    guarded(locale, || {
+        let puzzle = Puzzle::with_settings(
+            puzzle_data.width,
+            puzzle_data.height,
+            puzzle_data.row_hints,
+            puzzle_data.col_hints,
+            &stored.solver_settings(),
+        )
+        .map_err(|errors| localize_all(&errors, locale))?;
+        let outcome = if solved {
+            SolveOutcome::Solved
+        } else {
+            SolveOutcome::Abandoned
+        };
+        stats
+            .append(&SolveRecord::new(
+                puzzle.row_hints(),
+                puzzle.col_hints(),
+                puzzle_data.metadata.and_then(|m| m.difficulty),
+                SolveKind::Play,
+                outcome,
+                elapsed_ms,
+                0,
+            ))
+            .map_err(|e| e.localize(locale))
+    })
 }
 
 #[tauri::command]
-pub fn load_puzzle_file_command(path: String) -> Result<PuzzleData, String> {
-    file_io::load_puzzle_file(std::path::Path::new(&path))
+pub fn stats_summary_command(
+    group_by: GroupBy,
+    locale: Option<Locale>,
+    stats: State<'_, StatsLog>,
+    settings: State<'_, SettingsState>,
+) -> Result<Vec<StatsGroup>, String> {
+    let locale = locale.unwrap_or_else(|| settings.get().locale);
+    guarded(locale, || {
+        stats
+            .records()
+            .map(|records| stats::aggregate(&records, group_by))
+            .map_err(|e| e.localize(locale))
+    })
 }
 
+/// Fastest completed play session for a puzzle, or solver run with `kind`.
 #[tauri::command]
-pub fn save_puzzle_file_command(path: String, puzzle_data: PuzzleData) -> Result<(), String> {
-    file_io::save_puzzle_file(std::path::Path::new(&path), &puzzle_data)
+pub fn best_time_command(
+    row_hints: Vec<Vec<u32>>,
+    col_hints: Vec<Vec<u32>>,
+    kind: Option<SolveKind>,
+    locale: Option<Locale>,
+    stats: State<'_, StatsLog>,
+    settings: State<'_, SettingsState>,
+) -> Result<Option<u64>, String> {
+    let stored = settings.get();
+    let locale = locale.unwrap_or(stored.locale);
+    guarded(locale, || {
+        let puzzle = Puzzle::with_settings(
+            col_hints.len(),
+            row_hints.len(),
+            row_hints,
+            col_hints,
+            &stored.solver_settings(),
+        )
+        .map_err(|errors| localize_all(&errors, locale))?;
+        let hash = exact_hash(puzzle.row_hints(), puzzle.col_hints());
+        stats
+            .records()
+            .map(|records| stats::best_time(&records, &hash, kind.unwrap_or(SolveKind::Play)))
+            .map_err(|e| e.localize(locale))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guarded_reports_panics() {
+        let result: Result<(), String> = guarded(Locale::En, || panic!("index out of bounds"));
+        assert_eq!(
+            result,
+            Err("Internal error: index out of bounds".to_string())
+        );
+        let result: Result<(), String> =
+            guarded(Locale::En, || panic!("row {} of {}", 3, 2));
+        assert_eq!(result, Err("Internal error: row 3 of 2".to_string()));
+        assert_eq!(guarded(Locale::En, || Ok(1)), Ok(1));
+    }
 }
@@ -0,0 +1,542 @@
+//! C API for embedding the solver. `include/illust_logic_solver.h` is
+//! generated from this file with cbindgen; see `cbindgen.toml`.
+//!
+//! - Handles are opaque. Each one returned to the caller must be released
+//!   with its `_free` function; freeing NULL does nothing.
+//! - Functions that can fail return an `IlsStatus` and only write their out
+//!   pointer on success. `ils_last_error` then tells what went wrong.
+//! - Nothing unwinds into C: a panic becomes `ILS_STATUS_INTERNAL_ERROR`.
+//! - Messages are in English.
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+use std::slice;
+
+use crate::i18n::{Locale, Localize};
+use crate::puzzle::file_io;
+use crate::solver::backtrack;
+use crate::solver::options::SolverOptions;
+use crate::solver::puzzle::Puzzle;
+use crate::solver::types::{
+    SearchBudget, SolveResult, SolveStats, SolverSettings, ValidationError, GRID_SIZE_LIMIT,
+};
+use crate::solver::validator::validate_dimensions;
+use crate::unwind;
+
+/// Whether a call succeeded.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IlsStatus {
+    Ok = 0,
+    /// A required pointer was NULL or a string was not valid UTF-8.
+    InvalidArgument = 1,
+    /// The hints or the puzzle JSON were rejected.
+    InvalidPuzzle = 2,
+    /// A bug in the solver.
+    InternalError = 3,
+}
+
+/// What a solve found.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IlsOutcome {
+    UniqueSolution = 0,
+    /// At least two solutions; up to `max_solutions` of them, and never fewer
+    /// than two, are kept.
+    MultipleSolutions = 1,
+    NoSolution = 2,
+    /// The timeout passed before the solver could tell.
+    Timeout = 3,
+    /// The search budget ran out before the solver could tell.
+    BudgetExhausted = 4,
+}
+
+/// How a solve runs. Start from `ils_solve_options_default` and change the
+/// fields you need, so that code keeps working when fields are added.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IlsSolveOptions {
+    /// Solutions to keep. At least 2 are looked for whatever this says, so
+    /// that uniqueness is always known.
+    pub max_solutions: usize,
+    /// Give up after this many seconds; infinity for no limit.
+    pub timeout_seconds: f64,
+    /// Search nodes to visit at most; `UINT64_MAX` for no limit.
+    pub max_nodes: u64,
+    /// Rows and columns to solve at most; `UINT64_MAX` for no limit.
+    pub max_line_solver_calls: u64,
+}
+
+/// How the answer was reached.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IlsSolveStats {
+    pub elapsed_seconds: f64,
+    pub nodes: u64,
+    pub max_depth: u32,
+    pub line_solver_calls: u64,
+    pub cells_by_logic: u64,
+    pub cells_by_search: u64,
+}
+
+/// A validated puzzle.
+pub struct IlsPuzzle(Puzzle);
+
+/// The result of `ils_solve`.
+pub struct IlsResult {
+    result: SolveResult,
+    outcome: IlsOutcome,
+    /// Each solution row by row, one byte per cell.
+    solutions: Vec<Vec<u8>>,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// The library version, e.g. "0.1.1". The string is static.
+#[no_mangle]
+pub extern "C" fn ils_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast()
+}
+
+/// The message of the latest failed call on this thread, or NULL if none
+/// failed yet. The string stays valid until the next failure on this thread.
+#[no_mangle]
+pub extern "C" fn ils_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map_or(ptr::null(), |message| message.as_ptr())
+    })
+}
+
+/// Build a puzzle from its hints.
+///
+/// `row_lengths` holds `height` counts, the number of hints of each row, and
+/// `row_hints` all row hints one row after another; the columns likewise
+/// with `width` counts. A hint array may be NULL when it holds no hints.
+///
+/// # Safety
+/// The arrays must be as long as described above, and `out` must be valid
+/// for writes.
+#[no_mangle]
+pub unsafe extern "C" fn ils_puzzle_new(
+    width: usize,
+    height: usize,
+    row_hints: *const u32,
+    row_lengths: *const usize,
+    col_hints: *const u32,
+    col_lengths: *const usize,
+    out: *mut *mut IlsPuzzle,
+) -> IlsStatus {
+    guarded(|| {
+        if out.is_null() || row_lengths.is_null() || col_lengths.is_null() {
+            return Err(null_argument());
+        }
+        // The sizes give the array lengths, so they are checked before reading
+        let settings = SolverSettings {
+            max_grid_size: GRID_SIZE_LIMIT,
+        };
+        let errors = validate_dimensions(width, height, &settings);
+        if !errors.is_empty() {
+            return Err(invalid_puzzle(errors));
+        }
+        let rows = read_lines(row_hints, slice::from_raw_parts(row_lengths, height))?;
+        let cols = read_lines(col_hints, slice::from_raw_parts(col_lengths, width))?;
+        let puzzle = Puzzle::new(width, height, rows, cols).map_err(invalid_puzzle)?;
+        *out = Box::into_raw(Box::new(IlsPuzzle(puzzle)));
+        Ok(())
+    })
+}
+
+/// Build a puzzle from the contents of a puzzle file of any supported version.
+///
+/// # Safety
+/// `json` must be a NUL-terminated string and `out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn ils_puzzle_from_json(
+    json: *const c_char,
+    out: *mut *mut IlsPuzzle,
+) -> IlsStatus {
+    guarded(|| {
+        if json.is_null() || out.is_null() {
+            return Err(null_argument());
+        }
+        let json = CStr::from_ptr(json).to_str().map_err(|_| {
+            let message = "The JSON is not valid UTF-8".to_string();
+            (IlsStatus::InvalidArgument, message)
+        })?;
+        let data = file_io::parse_puzzle(json)
+            .map_err(|e| (IlsStatus::InvalidPuzzle, e.localize(Locale::En)))?;
+        let puzzle = Puzzle::try_from(&data).map_err(invalid_puzzle)?;
+        *out = Box::into_raw(Box::new(IlsPuzzle(puzzle)));
+        Ok(())
+    })
+}
+
+/// # Safety
+/// `puzzle` must come from `ils_puzzle_new` or `ils_puzzle_from_json`.
+#[no_mangle]
+pub unsafe extern "C" fn ils_puzzle_width(puzzle: *const IlsPuzzle) -> usize {
+    (*puzzle).0.width()
+}
+
+/// # Safety
+/// `puzzle` must come from `ils_puzzle_new` or `ils_puzzle_from_json`.
+#[no_mangle]
+pub unsafe extern "C" fn ils_puzzle_height(puzzle: *const IlsPuzzle) -> usize {
+    (*puzzle).0.height()
+}
+
+/// # Safety
+/// `puzzle` must be NULL or come from `ils_puzzle_new` or
+/// `ils_puzzle_from_json`, and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn ils_puzzle_free(puzzle: *mut IlsPuzzle) {
+    if !puzzle.is_null() {
+        drop(Box::from_raw(puzzle));
+    }
+}
+
+/// Two solutions, no timeout and no search budget.
+#[no_mangle]
+pub extern "C" fn ils_solve_options_default() -> IlsSolveOptions {
+    IlsSolveOptions {
+        max_solutions: 2,
+        timeout_seconds: f64::INFINITY,
+        max_nodes: u64::MAX,
+        max_line_solver_calls: u64::MAX,
+    }
+}
+
+/// Solve a puzzle. `options` may be NULL for the defaults.
+///
+/// # Safety
+/// `puzzle` must come from `ils_puzzle_new` or `ils_puzzle_from_json`,
+/// `options` must be NULL or valid for reads, and `out` valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn ils_solve(
+    puzzle: *const IlsPuzzle,
+    options: *const IlsSolveOptions,
+    out: *mut *mut IlsResult,
+) -> IlsStatus {
+    guarded(|| {
+        if puzzle.is_null() || out.is_null() {
+            return Err(null_argument());
+        }
+        let options = options
+            .as_ref()
+            .copied()
+            .unwrap_or_else(|| ils_solve_options_default());
+        let result = backtrack::solve(&(*puzzle).0, &solver_options(&options));
+        *out = Box::into_raw(Box::new(IlsResult::new(result)));
+        Ok(())
+    })
+}
+
+/// # Safety
+/// `result` must come from `ils_solve`.
+#[no_mangle]
+pub unsafe extern "C" fn ils_result_outcome(result: *const IlsResult) -> IlsOutcome {
+    (*result).outcome
+}
+
+/// Number of solutions kept: 1 for a unique solution, 0 if none was found.
+///
+/// # Safety
+/// `result` must come from `ils_solve`.
+#[no_mangle]
+pub unsafe extern "C" fn ils_result_solution_count(result: *const IlsResult) -> usize {
+    let result = &*result;
+    result.solutions.len()
+}
+
+/// The solution at `index`: width × height bytes row by row, 1 for a filled
+/// cell and 0 for an empty one. NULL if `index` is out of range. The bytes
+/// stay valid until the result is freed.
+///
+/// # Safety
+/// `result` must come from `ils_solve`.
+#[no_mangle]
+pub unsafe extern "C" fn ils_result_solution(result: *const IlsResult, index: usize) -> *const u8 {
+    let result = &*result;
+    result
+        .solutions
+        .get(index)
+        .map_or(ptr::null(), |cells| cells.as_ptr())
+}
+
+/// # Safety
+/// `result` must come from `ils_solve`.
+#[no_mangle]
+pub unsafe extern "C" fn ils_result_stats(result: *const IlsResult) -> IlsSolveStats {
+    let stats: &SolveStats = (*result).result.stats();
+    IlsSolveStats {
+        elapsed_seconds: stats.elapsed_seconds,
+        nodes: stats.nodes,
+        max_depth: stats.max_depth,
+        line_solver_calls: stats.line_solver_calls,
+        cells_by_logic: stats.cells_by_logic,
+        cells_by_search: stats.cells_by_search,
+    }
+}
+
+/// The result as the JSON the app uses, e.g. `{"type":"UniqueSolution",...}`.
+/// Release it with `ils_string_free`.
+///
+/// # Safety
+/// `result` must come from `ils_solve`.
+#[no_mangle]
+pub unsafe extern "C" fn ils_result_to_json(result: *const IlsResult) -> *mut c_char {
+    let json = serde_json::to_string(&(*result).result).expect("results serialize");
+    // JSON escapes control characters, so there is no NUL inside
+    CString::new(json).expect("no NUL in JSON").into_raw()
+}
+
+/// # Safety
+/// `result` must be NULL or come from `ils_solve`, and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn ils_result_free(result: *mut IlsResult) {
+    if !result.is_null() {
+        drop(Box::from_raw(result));
+    }
+}
+
+/// # Safety
+/// `string` must be NULL or a string this library returned for freeing,
+/// and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn ils_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+impl IlsResult {
+    fn new(result: SolveResult) -> Self {
+        let (outcome, grids) = match &result {
+            SolveResult::UniqueSolution { grid, .. } => {
+                (IlsOutcome::UniqueSolution, slice::from_ref(grid))
+            }
+            SolveResult::MultipleSolutions { grids, .. } => {
+                (IlsOutcome::MultipleSolutions, grids.as_slice())
+            }
+            SolveResult::NoSolution { .. } => (IlsOutcome::NoSolution, &[][..]),
+            SolveResult::Timeout { .. } => (IlsOutcome::Timeout, &[][..]),
+            SolveResult::BudgetExhausted { .. } => (IlsOutcome::BudgetExhausted, &[][..]),
+            SolveResult::InvalidInput { .. } => unreachable!("puzzles are validated"),
+        };
+        let solutions = grids.iter().map(|grid| grid.concat()).collect();
+        IlsResult {
+            result,
+            outcome,
+            solutions,
+        }
+    }
+}
+
+type Failure = (IlsStatus, String);
+
+/// Run a call's body, recording a failure or panic for `ils_last_error`.
+fn guarded(body: impl FnOnce() -> Result<(), Failure>) -> IlsStatus {
+    let (status, message) = match unwind::catch(body) {
+        Ok(Ok(())) => return IlsStatus::Ok,
+        Ok(Err(failure)) => failure,
+        Err(message) => (
+            IlsStatus::InternalError,
+            format!("Internal error: {}", message),
+        ),
+    };
+    // A message cannot hold NUL in C; it would end the string early anyway
+    let message = CString::new(message.replace('\0', "")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+    status
+}
+
+fn null_argument() -> Failure {
+    let message = "A required pointer is NULL".to_string();
+    (IlsStatus::InvalidArgument, message)
+}
+
+fn invalid_puzzle(errors: Vec<ValidationError>) -> Failure {
+    let messages: Vec<String> = errors.iter().map(|e| e.localize(Locale::En)).collect();
+    (IlsStatus::InvalidPuzzle, messages.join("; "))
+}
+
+/// Split `hints` into lines of the given lengths.
+unsafe fn read_lines(hints: *const u32, lengths: &[usize]) -> Result<Vec<Vec<u32>>, Failure> {
+    let total = lengths
+        .iter()
+        .try_fold(0usize, |sum, &len| sum.checked_add(len))
+        .ok_or_else(|| {
+            let message = "The hint counts add up to more than memory can hold".to_string();
+            (IlsStatus::InvalidArgument, message)
+        })?;
+    if total == 0 {
+        return Ok(vec![vec![]; lengths.len()]);
+    }
+    if hints.is_null() {
+        return Err(null_argument());
+    }
+    let mut rest = slice::from_raw_parts(hints, total);
+    Ok(lengths
+        .iter()
+        .map(|&len| {
+            let (line, after) = rest.split_at(len);
+            rest = after;
+            line.to_vec()
+        })
+        .collect())
+}
+
+fn solver_options(options: &IlsSolveOptions) -> SolverOptions {
+    let limit = |value: u64| (value != u64::MAX).then_some(value);
+    SolverOptions {
+        max_solutions: options.max_solutions,
+        timeout_seconds: options
+            .timeout_seconds
+            .is_finite()
+            .then_some(options.timeout_seconds),
+        budget: SearchBudget {
+            max_nodes: limit(options.max_nodes),
+            max_line_solver_calls: limit(options.max_line_solver_calls),
+        },
+        ..SolverOptions::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The 3x3 cross, □■□ / ■■■ / □■□.
+    fn cross() -> *mut IlsPuzzle {
+        let hints = [1, 3, 1];
+        let lengths = [1, 1, 1];
+        let mut puzzle = ptr::null_mut();
+        let status = unsafe {
+            ils_puzzle_new(
+                3,
+                3,
+                hints.as_ptr(),
+                lengths.as_ptr(),
+                hints.as_ptr(),
+                lengths.as_ptr(),
+                &mut puzzle,
+            )
+        };
+        assert_eq!(status, IlsStatus::Ok);
+        puzzle
+    }
+
+    fn last_error() -> String {
+        unsafe { CStr::from_ptr(ils_last_error()) }
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn test_solve_through_c_api() {
+        let puzzle = cross();
+        let mut result = ptr::null_mut();
+        unsafe {
+            assert_eq!(
+                (ils_puzzle_width(puzzle), ils_puzzle_height(puzzle)),
+                (3, 3)
+            );
+            assert_eq!(ils_solve(puzzle, ptr::null(), &mut result), IlsStatus::Ok);
+            assert_eq!(ils_result_outcome(result), IlsOutcome::UniqueSolution);
+            assert_eq!(ils_result_solution_count(result), 1);
+            let cells = slice::from_raw_parts(ils_result_solution(result, 0), 9);
+            assert_eq!(cells, [0, 1, 0, 1, 1, 1, 0, 1, 0]);
+            assert!(ils_result_solution(result, 1).is_null());
+            assert_eq!(ils_result_stats(result).cells_by_logic, 9);
+
+            let json = ils_result_to_json(result);
+            let text = CStr::from_ptr(json).to_str().unwrap();
+            assert!(text.starts_with(r#"{"type":"UniqueSolution""#), "{}", text);
+            ils_string_free(json);
+            ils_result_free(result);
+
+            let options = IlsSolveOptions {
+                max_nodes: 0,
+                ..ils_solve_options_default()
+            };
+            assert_eq!(ils_solve(puzzle, &options, &mut result), IlsStatus::Ok);
+            assert_eq!(ils_result_outcome(result), IlsOutcome::BudgetExhausted);
+            assert!(ils_result_solution(result, 0).is_null());
+            ils_result_free(result);
+            ils_puzzle_free(puzzle);
+        }
+    }
+
+    #[test]
+    fn test_rejected_puzzles() {
+        let mut puzzle = ptr::null_mut();
+        let lengths = [1];
+        let status = unsafe {
+            ils_puzzle_new(
+                1,
+                1,
+                [4].as_ptr(),
+                lengths.as_ptr(),
+                [1].as_ptr(),
+                lengths.as_ptr(),
+                &mut puzzle,
+            )
+        };
+        assert_eq!(status, IlsStatus::InvalidPuzzle);
+        assert!(puzzle.is_null());
+        assert_eq!(
+            last_error(),
+            "Row 1 needs at least 4 cells, but the width is 1"
+        );
+
+        // Sizes beyond the limit are rejected without reading the arrays
+        let status = unsafe {
+            ils_puzzle_new(
+                usize::MAX,
+                1,
+                ptr::null(),
+                lengths.as_ptr(),
+                ptr::null(),
+                lengths.as_ptr(),
+                &mut puzzle,
+            )
+        };
+        assert_eq!(status, IlsStatus::InvalidPuzzle);
+        assert!(last_error().contains("width"), "{}", last_error());
+
+        let status = unsafe { ils_puzzle_from_json(ptr::null(), &mut puzzle) };
+        assert_eq!(status, IlsStatus::InvalidArgument);
+        let json = CString::new(r#"{"version": "1.0", "width": 1}"#).unwrap();
+        let status = unsafe { ils_puzzle_from_json(json.as_ptr(), &mut puzzle) };
+        assert_eq!(status, IlsStatus::InvalidPuzzle);
+        assert!(puzzle.is_null());
+    }
+
+    #[test]
+    fn test_puzzle_from_json() {
+        let json = CString::new(
+            r#"{"version": "1.0", "width": 2, "height": 1,
+                "rowHints": [[2]], "colHints": [[1], [1]], "metadata": null}"#,
+        )
+        .unwrap();
+        let mut puzzle = ptr::null_mut();
+        unsafe {
+            assert_eq!(
+                ils_puzzle_from_json(json.as_ptr(), &mut puzzle),
+                IlsStatus::Ok
+            );
+            assert_eq!(
+                (ils_puzzle_width(puzzle), ils_puzzle_height(puzzle)),
+                (2, 1)
+            );
+            ils_puzzle_free(puzzle);
+            ils_puzzle_free(ptr::null_mut());
+        }
+    }
+}
@@ -0,0 +1,404 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::puzzle::data::PuzzleData;
+use crate::puzzle::file_io::{self, FileError};
+use crate::puzzle::migration::CURRENT_VERSION;
+use crate::solver::types::{CellState, Grid};
+
+pub const GAMES_DIR_NAME: &str = "games";
+
+/// Saved-game format version written by this build.
+pub const GAME_VERSION: u32 = 1;
+
+/// The slot overwritten by every autosave.
+pub const AUTOSAVE_ID: &str = "autosave";
+
+/// A cell the player changed, with its state before and after.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CellChange {
+    pub row: usize,
+    pub col: usize,
+    pub before: CellState,
+    pub after: CellState,
+}
+
+/// One undoable action; a drag across several cells is a single move.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Move {
+    pub changes: Vec<CellChange>,
+}
+
+/// A puzzle together with the player's progress on it.
+///
+/// `grid` uses all three cell states: `Empty` is a cell the player crossed out.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedGame {
+    pub version: u32,
+    pub puzzle: PuzzleData,
+    /// Library entry the puzzle was opened from, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub library_id: Option<String>,
+    pub grid: Grid,
+    pub elapsed_seconds: u64,
+    /// Undo history, oldest move first.
+    #[serde(default)]
+    pub history: Vec<Move>,
+    /// Seconds since the Unix epoch, set when the game is written.
+    #[serde(default)]
+    pub saved_at: u64,
+}
+
+/// What the resume list shows for each saved game.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameSummary {
+    pub id: String,
+    pub title: Option<String>,
+    pub width: usize,
+    pub height: usize,
+    pub library_id: Option<String>,
+    pub elapsed_seconds: u64,
+    pub saved_at: u64,
+    pub autosave: bool,
+}
+
+impl GameSummary {
+    fn new(id: &str, game: &SavedGame) -> Self {
+        GameSummary {
+            id: id.to_string(),
+            title: game.puzzle.metadata.as_ref().and_then(|m| m.title.clone()),
+            width: game.puzzle.width,
+            height: game.puzzle.height,
+            library_id: game.library_id.clone(),
+            elapsed_seconds: game.elapsed_seconds,
+            saved_at: game.saved_at,
+            autosave: id == AUTOSAVE_ID,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameError {
+    Read(String),
+    InvalidJson(String),
+    /// The file was written by a newer version of the app.
+    UnsupportedVersion {
+        found: u64,
+        supported: u32,
+    },
+    /// The embedded puzzle could not be used.
+    Puzzle(FileError),
+    /// The grid or the undo history does not fit the puzzle's size.
+    ProgressMismatch,
+    /// Ids name files, so only ASCII letters, digits, `-` and `_` are allowed.
+    InvalidId(String),
+    NotFound(String),
+    Serialize(String),
+    Write(String),
+}
+
+/// Parse a saved game, migrating the embedded puzzle from older formats.
+pub fn parse_game(content: &str) -> Result<SavedGame, GameError> {
+    let value: Value =
+        serde_json::from_str(content).map_err(|e| GameError::InvalidJson(e.to_string()))?;
+    let Value::Object(mut object) = value else {
+        return Err(GameError::InvalidJson("expected a JSON object".to_string()));
+    };
+    let found = object
+        .get("version")
+        .and_then(Value::as_u64)
+        .ok_or_else(|| GameError::InvalidJson("missing version".to_string()))?;
+    if found > GAME_VERSION as u64 {
+        return Err(GameError::UnsupportedVersion {
+            found,
+            supported: GAME_VERSION,
+        });
+    }
+
+    let puzzle = object
+        .remove("puzzle")
+        .ok_or_else(|| GameError::InvalidJson("missing puzzle".to_string()))?;
+    let puzzle = file_io::puzzle_from_value(puzzle).map_err(GameError::Puzzle)?;
+    object.insert(
+        "puzzle".to_string(),
+        serde_json::to_value(puzzle).map_err(|e| GameError::InvalidJson(e.to_string()))?,
+    );
+    let game: SavedGame = serde_json::from_value(Value::Object(object))
+        .map_err(|e| GameError::InvalidJson(e.to_string()))?;
+    check_progress(&game)?;
+    Ok(game)
+}
+
+fn check_progress(game: &SavedGame) -> Result<(), GameError> {
+    let (width, height) = (game.puzzle.width, game.puzzle.height);
+    let grid_fits = game.grid.len() == height && game.grid.iter().all(|row| row.len() == width);
+    let history_fits = game
+        .history
+        .iter()
+        .flat_map(|m| &m.changes)
+        .all(|c| c.row < height && c.col < width);
+    if grid_fits && history_fits {
+        Ok(())
+    } else {
+        Err(GameError::ProgressMismatch)
+    }
+}
+
+fn check_id(id: &str) -> Result<(), GameError> {
+    let valid = !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(GameError::InvalidId(id.to_string()))
+    }
+}
+
+/// Saved games, one file per game in the app data directory.
+pub struct GameStore {
+    dir: PathBuf,
+}
+
+impl GameStore {
+    pub fn new(dir: PathBuf) -> Self {
+        GameStore { dir }
+    }
+
+    /// Write a game under `id`, or under a fresh id when none is given.
+    /// The version and save time are stamped on the written copy.
+    /// A game that could not be loaded again is refused.
+    pub fn save(&self, id: Option<&str>, game: &SavedGame) -> Result<GameSummary, GameError> {
+        file_io::check_puzzle(&game.puzzle).map_err(GameError::Puzzle)?;
+        check_progress(game)?;
+        let id = match id {
+            Some(id) => {
+                check_id(id)?;
+                id.to_string()
+            }
+            None => (1..)
+                .map(|n| format!("game-{}", n))
+                .find(|id| !self.game_path(id).exists())
+                .unwrap(),
+        };
+        let saved_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let game = SavedGame {
+            version: GAME_VERSION,
+            puzzle: PuzzleData {
+                version: CURRENT_VERSION.to_string(),
+                ..game.puzzle.clone()
+            },
+            saved_at,
+            ..game.clone()
+        };
+
+        let json =
+            serde_json::to_string_pretty(&game).map_err(|e| GameError::Serialize(e.to_string()))?;
+        fs::create_dir_all(&self.dir).map_err(|e| GameError::Write(e.to_string()))?;
+        fs::write(self.game_path(&id), json).map_err(|e| GameError::Write(e.to_string()))?;
+        Ok(GameSummary::new(&id, &game))
+    }
+
+    pub fn load(&self, id: &str) -> Result<SavedGame, GameError> {
+        check_id(id)?;
+        let path = self.game_path(id);
+        if !path.exists() {
+            return Err(GameError::NotFound(id.to_string()));
+        }
+        let content = fs::read_to_string(path).map_err(|e| GameError::Read(e.to_string()))?;
+        parse_game(&content)
+    }
+
+    /// Every readable saved game, most recently saved first.
+    /// Files that cannot be parsed are left out rather than failing the list.
+    pub fn list(&self) -> Result<Vec<GameSummary>, GameError> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut games = Vec::new();
+        for item in fs::read_dir(&self.dir).map_err(|e| GameError::Read(e.to_string()))? {
+            let path = item.map_err(|e| GameError::Read(e.to_string()))?.path();
+            if path.extension().map_or(true, |ext| ext != "json") {
+                continue;
+            }
+            let Some(id) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            if let Ok(game) = self.load(id) {
+                games.push(GameSummary::new(id, &game));
+            }
+        }
+        games.sort_by(|a, b| b.saved_at.cmp(&a.saved_at).then_with(|| a.id.cmp(&b.id)));
+        Ok(games)
+    }
+
+    pub fn remove(&self, id: &str) -> Result<(), GameError> {
+        check_id(id)?;
+        let path = self.game_path(id);
+        if !path.exists() {
+            return Err(GameError::NotFound(id.to_string()));
+        }
+        fs::remove_file(path).map_err(|e| GameError::Write(e.to_string()))
+    }
+
+    fn game_path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_store(name: &str) -> GameStore {
+        GameStore::new(std::env::temp_dir().join(format!(
+            "illust-logic-games-{}-{}",
+            std::process::id(),
+            name
+        )))
+    }
+
+    fn sample_game() -> SavedGame {
+        let puzzle =
+            file_io::parse_puzzle(include_str!("../../docs/samples/easy_5x5.json")).unwrap();
+        let mut grid = vec![vec![CellState::Unknown; 5]; 5];
+        grid[2] = vec![CellState::Filled; 5];
+        grid[0][2] = CellState::Empty;
+        SavedGame {
+            version: GAME_VERSION,
+            puzzle,
+            library_id: Some("easy_5x5".to_string()),
+            grid,
+            elapsed_seconds: 42,
+            history: vec![
+                Move {
+                    changes: (0..5)
+                        .map(|col| CellChange {
+                            row: 2,
+                            col,
+                            before: CellState::Unknown,
+                            after: CellState::Filled,
+                        })
+                        .collect(),
+                },
+                Move {
+                    changes: vec![CellChange {
+                        row: 0,
+                        col: 2,
+                        before: CellState::Unknown,
+                        after: CellState::Empty,
+                    }],
+                },
+            ],
+            saved_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_save_and_resume() {
+        let store = temp_store("resume");
+        let game = sample_game();
+        let summary = store.save(None, &game).unwrap();
+        assert_eq!(summary.id, "game-1");
+        assert_eq!(summary.title.as_deref(), Some("簡単な5x5問題"));
+        assert!(!summary.autosave);
+
+        let loaded = store.load("game-1").unwrap();
+        assert!(loaded.saved_at > 0);
+        assert_eq!(
+            loaded,
+            SavedGame {
+                saved_at: loaded.saved_at,
+                ..game
+            }
+        );
+        fs::remove_dir_all(&store.dir).unwrap();
+    }
+
+    #[test]
+    fn test_list_and_remove() {
+        let store = temp_store("list");
+        assert_eq!(store.list().unwrap(), Vec::new());
+        store.save(None, &sample_game()).unwrap();
+        store.save(Some(AUTOSAVE_ID), &sample_game()).unwrap();
+        fs::write(store.dir.join("broken.json"), "{").unwrap();
+
+        let games = store.list().unwrap();
+        assert_eq!(games.len(), 2);
+        assert_eq!(games.iter().filter(|g| g.autosave).count(), 1);
+
+        store.remove("game-1").unwrap();
+        assert_eq!(
+            store.load("game-1"),
+            Err(GameError::NotFound("game-1".to_string()))
+        );
+        fs::remove_dir_all(&store.dir).unwrap();
+    }
+
+    #[test]
+    fn test_reject_mismatched_progress() {
+        let store = temp_store("mismatch");
+        let mut game = sample_game();
+        game.grid.pop();
+        assert_eq!(store.save(None, &game), Err(GameError::ProgressMismatch));
+
+        let mut game = sample_game();
+        game.history[1].changes[0].col = 5;
+        let json = serde_json::to_string(&game).unwrap();
+        assert_eq!(parse_game(&json), Err(GameError::ProgressMismatch));
+
+        assert_eq!(
+            store.save(Some("../escape"), &sample_game()),
+            Err(GameError::InvalidId("../escape".to_string()))
+        );
+    }
+
+    // Such a game would save, then fail to load and drop out of the list
+    #[test]
+    fn test_reject_unloadable_puzzle() {
+        let store = temp_store("unloadable");
+        let mut game = sample_game();
+        game.puzzle.row_hints[0] = vec![6];
+        assert!(matches!(
+            store.save(None, &game),
+            Err(GameError::Puzzle(FileError::InvalidStructure { .. }))
+        ));
+
+        let mut game = sample_game();
+        game.puzzle.solution = Some("!".to_string());
+        assert!(matches!(
+            store.save(None, &game),
+            Err(GameError::Puzzle(FileError::InvalidSolution(_)))
+        ));
+        assert!(!store.dir.exists());
+    }
+
+    #[test]
+    fn test_embedded_puzzle_is_migrated() {
+        let mut value = serde_json::to_value(sample_game()).unwrap();
+        value["puzzle"] = serde_json::from_str(include_str!("puzzle/testdata/v1_0.json")).unwrap();
+        let game = parse_game(&value.to_string()).unwrap();
+        assert_eq!(game.puzzle.version, CURRENT_VERSION);
+        assert!(game.puzzle.solution.is_some());
+
+        value["version"] = Value::from(GAME_VERSION + 1);
+        assert_eq!(
+            parse_game(&value.to_string()),
+            Err(GameError::UnsupportedVersion {
+                found: GAME_VERSION as u64 + 1,
+                supported: GAME_VERSION,
+            })
+        );
+    }
+}
@@ -0,0 +1,649 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "desktop")]
+use crate::commands::CommandError;
+#[cfg(feature = "desktop")]
+use crate::game::GameError;
+#[cfg(feature = "desktop")]
+use crate::library::LibraryError;
+use crate::play::MoveCheckError;
+use crate::puzzle::file_io::FileError;
+use crate::puzzle::solution::SolutionError;
+#[cfg(feature = "server")]
+use crate::server::ServerError;
+#[cfg(feature = "desktop")]
+use crate::settings::SettingsError;
+#[cfg(feature = "desktop")]
+use crate::stats::StatsError;
+use crate::solver::types::{Axis, Dimension, SolveResult, ValidationError};
+
+/// Language used for user-facing backend messages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    Ja,
+    En,
+}
+
+/// Render a value as a message in the given locale.
+pub trait Localize {
+    fn localize(&self, locale: Locale) -> String;
+}
+
+fn line_name(axis: Axis, locale: Locale) -> &'static str {
+    match (axis, locale) {
+        (Axis::Row, Locale::Ja) => "行",
+        (Axis::Column, Locale::Ja) => "列",
+        (Axis::Row, Locale::En) => "Row",
+        (Axis::Column, Locale::En) => "Column",
+    }
+}
+
+fn dimension_name(dimension: Dimension, locale: Locale) -> &'static str {
+    match (dimension, locale) {
+        (Dimension::Width, Locale::Ja) => "幅",
+        (Dimension::Height, Locale::Ja) => "高さ",
+        (Dimension::Width, Locale::En) => "width",
+        (Dimension::Height, Locale::En) => "height",
+    }
+}
+
+/// The dimension that bounds the lines of an axis (rows span the width).
+fn line_length(axis: Axis) -> Dimension {
+    match axis {
+        Axis::Row => Dimension::Width,
+        Axis::Column => Dimension::Height,
+    }
+}
+
+/// The dimension that must match the number of lines of an axis.
+fn line_count(axis: Axis) -> Dimension {
+    match axis {
+        Axis::Row => Dimension::Height,
+        Axis::Column => Dimension::Width,
+    }
+}
+
+impl Localize for ValidationError {
+    fn localize(&self, locale: Locale) -> String {
+        match (self, locale) {
+            (
+                ValidationError::DimensionOutOfRange {
+                    dimension,
+                    value,
+                    min,
+                    max,
+                },
+                Locale::Ja,
+            ) => format!(
+                "{}は{}以上{}以下である必要があります（現在: {}）",
+                dimension_name(*dimension, locale),
+                min,
+                max,
+                value
+            ),
+            (
+                ValidationError::DimensionOutOfRange {
+                    dimension,
+                    value,
+                    min,
+                    max,
+                },
+                Locale::En,
+            ) => format!(
+                "The {} must be between {} and {} (currently {})",
+                dimension_name(*dimension, locale),
+                min,
+                max,
+                value
+            ),
+            (
+                ValidationError::HintCountMismatch {
+                    axis,
+                    count,
+                    expected,
+                },
+                Locale::Ja,
+            ) => format!(
+                "{}ヒントの数（{}）が{}（{}）と一致しません",
+                line_name(*axis, locale),
+                count,
+                dimension_name(line_count(*axis), locale),
+                expected
+            ),
+            (
+                ValidationError::HintCountMismatch {
+                    axis,
+                    count,
+                    expected,
+                },
+                Locale::En,
+            ) => format!(
+                "The number of {} hints ({}) does not match the {} ({})",
+                line_name(*axis, locale).to_lowercase(),
+                count,
+                dimension_name(line_count(*axis), locale),
+                expected
+            ),
+            (
+                ValidationError::HintTooLong {
+                    axis,
+                    index,
+                    needed,
+                    available,
+                },
+                Locale::Ja,
+            ) => format!(
+                "{}{}のヒントは最低{}マス必要ですが、{}は{}です",
+                line_name(*axis, locale),
+                index + 1,
+                needed,
+                dimension_name(line_length(*axis), locale),
+                available
+            ),
+            (
+                ValidationError::HintTooLong {
+                    axis,
+                    index,
+                    needed,
+                    available,
+                },
+                Locale::En,
+            ) => format!(
+                "{} {} needs at least {} cells, but the {} is {}",
+                line_name(*axis, locale),
+                index + 1,
+                needed,
+                dimension_name(line_length(*axis), locale),
+                available
+            ),
+            (
+                ValidationError::ZeroInMultiHint {
+                    axis,
+                    index,
+                    position,
+                },
+                Locale::Ja,
+            ) => format!(
+                "{}{}のヒント位置{}に0が含まれています",
+                line_name(*axis, locale),
+                index + 1,
+                position + 1
+            ),
+            (
+                ValidationError::ZeroInMultiHint {
+                    axis,
+                    index,
+                    position,
+                },
+                Locale::En,
+            ) => format!(
+                "{} {} has a 0 at hint position {}",
+                line_name(*axis, locale),
+                index + 1,
+                position + 1
+            ),
+            (
+                ValidationError::SumMismatch {
+                    row_total,
+                    col_total,
+                },
+                Locale::Ja,
+            ) => format!(
+                "行ヒントの合計（{}）と列ヒントの合計（{}）が一致しません",
+                row_total, col_total
+            ),
+            (
+                ValidationError::SumMismatch {
+                    row_total,
+                    col_total,
+                },
+                Locale::En,
+            ) => format!(
+                "The row hint total ({}) does not match the column hint total ({})",
+                row_total, col_total
+            ),
+            (
+                ValidationError::FillCountUnreachable {
+                    axis,
+                    index,
+                    needed,
+                    possible,
+                },
+                Locale::Ja,
+            ) => format!(
+                "{}{}は{}マス塗る必要がありますが、交差する線から塗れるマスは{}個しかありません",
+                line_name(*axis, locale),
+                index + 1,
+                needed,
+                possible
+            ),
+            (
+                ValidationError::FillCountUnreachable {
+                    axis,
+                    index,
+                    needed,
+                    possible,
+                },
+                Locale::En,
+            ) => format!(
+                "{} {} needs {} filled cells, but the crossing lines allow only {}",
+                line_name(*axis, locale),
+                index + 1,
+                needed,
+                possible
+            ),
+            (
+                ValidationError::FillCountExceeded {
+                    axis,
+                    index,
+                    needed,
+                    forced,
+                },
+                Locale::Ja,
+            ) => format!(
+                "{}{}は{}マスしか塗れませんが、交差する線から{}マスが塗りに確定します",
+                line_name(*axis, locale),
+                index + 1,
+                needed,
+                forced
+            ),
+            (
+                ValidationError::FillCountExceeded {
+                    axis,
+                    index,
+                    needed,
+                    forced,
+                },
+                Locale::En,
+            ) => format!(
+                "{} {} allows {} filled cells, but the crossing lines force {}",
+                line_name(*axis, locale),
+                index + 1,
+                needed,
+                forced
+            ),
+            (ValidationError::LineContradiction { axis, index }, Locale::Ja) => format!(
+                "{}{}のヒントは交差する線と矛盾しています",
+                line_name(*axis, locale),
+                index + 1
+            ),
+            (ValidationError::LineContradiction { axis, index }, Locale::En) => format!(
+                "{} {} contradicts its crossing lines",
+                line_name(*axis, locale),
+                index + 1
+            ),
+        }
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.localize(Locale::default()))
+    }
+}
+
+impl Localize for FileError {
+    fn localize(&self, locale: Locale) -> String {
+        match (self, locale) {
+            (FileError::Read(e), Locale::Ja) => format!("ファイルの読み込みに失敗しました: {}", e),
+            (FileError::Read(e), Locale::En) => format!("Failed to read the file: {}", e),
+            (FileError::InvalidJson(e), Locale::Ja) => format!("JSON形式が不正です: {}", e),
+            (FileError::InvalidJson(e), Locale::En) => format!("Invalid JSON: {}", e),
+            (FileError::UnsupportedVersion { found, supported }, Locale::Ja) => format!(
+                "ファイルのバージョン（{}）はこのアプリ（{}まで対応）より新しいため読み込めません",
+                found, supported
+            ),
+            (FileError::UnsupportedVersion { found, supported }, Locale::En) => format!(
+                "The file version ({}) is newer than this app supports (up to {})",
+                found, supported
+            ),
+            (FileError::UnknownVersion(version), Locale::Ja) => {
+                format!("不明なファイルバージョンです: {}", version)
+            }
+            (FileError::UnknownVersion(version), Locale::En) => {
+                format!("Unknown file version: {}", version)
+            }
+            (FileError::InvalidStructure { path, error }, _) => {
+                format!("{}: {}", path, error.localize(locale))
+            }
+            (FileError::InvalidSolution(SolutionError::Malformed), Locale::Ja) => {
+                "保存されている解答データが不正です".to_string()
+            }
+            (FileError::InvalidSolution(SolutionError::Malformed), Locale::En) => {
+                "The stored solution is malformed".to_string()
+            }
+            (FileError::InvalidSolution(SolutionError::HintMismatch), Locale::Ja) => {
+                "保存されている解答がヒントと一致しません".to_string()
+            }
+            (FileError::InvalidSolution(SolutionError::HintMismatch), Locale::En) => {
+                "The stored solution does not match the hints".to_string()
+            }
+            (FileError::Serialize(e), Locale::Ja) => format!("シリアライズに失敗しました: {}", e),
+            (FileError::Serialize(e), Locale::En) => format!("Failed to serialize: {}", e),
+            (FileError::Write(e), Locale::Ja) => format!("ファイルの書き込みに失敗しました: {}", e),
+            (FileError::Write(e), Locale::En) => format!("Failed to write the file: {}", e),
+        }
+    }
+}
+
+impl fmt::Display for FileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.localize(Locale::default()))
+    }
+}
+
+#[cfg(feature = "desktop")]
+impl Localize for SettingsError {
+    fn localize(&self, locale: Locale) -> String {
+        match (self, locale) {
+            (SettingsError::Read(e), Locale::Ja) => format!("設定の読み込みに失敗しました: {}", e),
+            (SettingsError::Read(e), Locale::En) => format!("Failed to read the settings: {}", e),
+            (SettingsError::InvalidJson(e), Locale::Ja) => format!("設定ファイルが不正です: {}", e),
+            (SettingsError::InvalidJson(e), Locale::En) => format!("Invalid settings file: {}", e),
+            (SettingsError::UnsupportedVersion { found, supported }, Locale::Ja) => format!(
+                "設定ファイルのバージョン（{}）はこのアプリ（{}まで対応）より新しいため読み込めません",
+                found, supported
+            ),
+            (SettingsError::UnsupportedVersion { found, supported }, Locale::En) => format!(
+                "The settings file version ({}) is newer than this app supports (up to {})",
+                found, supported
+            ),
+            (SettingsError::Serialize(e), Locale::Ja) => format!("シリアライズに失敗しました: {}", e),
+            (SettingsError::Serialize(e), Locale::En) => format!("Failed to serialize: {}", e),
+            (SettingsError::Write(e), Locale::Ja) => format!("設定の保存に失敗しました: {}", e),
+            (SettingsError::Write(e), Locale::En) => format!("Failed to save the settings: {}", e),
+        }
+    }
+}
+
+#[cfg(feature = "desktop")]
+impl Localize for LibraryError {
+    fn localize(&self, locale: Locale) -> String {
+        match (self, locale) {
+            (LibraryError::Read(e), Locale::Ja) => {
+                format!("ライブラリの読み込みに失敗しました: {}", e)
+            }
+            (LibraryError::Read(e), Locale::En) => format!("Failed to read the library: {}", e),
+            (LibraryError::InvalidJson(e), Locale::Ja) => {
+                format!("ライブラリの索引ファイルが不正です: {}", e)
+            }
+            (LibraryError::InvalidJson(e), Locale::En) => {
+                format!("Invalid library index: {}", e)
+            }
+            (LibraryError::Serialize(e), Locale::Ja) => format!("シリアライズに失敗しました: {}", e),
+            (LibraryError::Serialize(e), Locale::En) => format!("Failed to serialize: {}", e),
+            (LibraryError::Write(e), Locale::Ja) => {
+                format!("ライブラリの保存に失敗しました: {}", e)
+            }
+            (LibraryError::Write(e), Locale::En) => format!("Failed to save the library: {}", e),
+            (LibraryError::NotFound(id), Locale::Ja) => {
+                format!("ライブラリに問題が見つかりません: {}", id)
+            }
+            (LibraryError::NotFound(id), Locale::En) => {
+                format!("No puzzle in the library with id: {}", id)
+            }
+            (LibraryError::UnsupportedVersion { found, supported }, Locale::Ja) => format!(
+                "ライブラリの索引（バージョン{}）はこのアプリ（{}まで対応）より新しいため読み込めません",
+                found, supported
+            ),
+            (LibraryError::UnsupportedVersion { found, supported }, Locale::En) => format!(
+                "The library index (version {}) is newer than this app supports (up to {})",
+                found, supported
+            ),
+            (LibraryError::Puzzle(e), _) => e.localize(locale),
+        }
+    }
+}
+
+#[cfg(feature = "desktop")]
+impl Localize for GameError {
+    fn localize(&self, locale: Locale) -> String {
+        match (self, locale) {
+            (GameError::Read(e), Locale::Ja) => {
+                format!("保存データの読み込みに失敗しました: {}", e)
+            }
+            (GameError::Read(e), Locale::En) => format!("Failed to read the saved game: {}", e),
+            (GameError::InvalidJson(e), Locale::Ja) => format!("保存データが不正です: {}", e),
+            (GameError::InvalidJson(e), Locale::En) => format!("Invalid saved game: {}", e),
+            (GameError::UnsupportedVersion { found, supported }, Locale::Ja) => format!(
+                "保存データのバージョン（{}）はこのアプリ（{}まで対応）より新しいため読み込めません",
+                found, supported
+            ),
+            (GameError::UnsupportedVersion { found, supported }, Locale::En) => format!(
+                "The saved game version ({}) is newer than this app supports (up to {})",
+                found, supported
+            ),
+            (GameError::Puzzle(e), _) => e.localize(locale),
+            (GameError::ProgressMismatch, Locale::Ja) => {
+                "保存された盤面が問題のサイズと一致しません".to_string()
+            }
+            (GameError::ProgressMismatch, Locale::En) => {
+                "The saved grid does not match the puzzle size".to_string()
+            }
+            (GameError::InvalidId(id), Locale::Ja) => format!("保存名が不正です: {}", id),
+            (GameError::InvalidId(id), Locale::En) => format!("Invalid save name: {}", id),
+            (GameError::NotFound(id), Locale::Ja) => {
+                format!("保存データが見つかりません: {}", id)
+            }
+            (GameError::NotFound(id), Locale::En) => format!("No saved game named: {}", id),
+            (GameError::Serialize(e), Locale::Ja) => format!("シリアライズに失敗しました: {}", e),
+            (GameError::Serialize(e), Locale::En) => format!("Failed to serialize: {}", e),
+            (GameError::Write(e), Locale::Ja) => {
+                format!("保存データの書き込みに失敗しました: {}", e)
+            }
+            (GameError::Write(e), Locale::En) => {
+                format!("Failed to write the saved game: {}", e)
+            }
+        }
+    }
+}
+
+impl Localize for MoveCheckError {
+    fn localize(&self, locale: Locale) -> String {
+        match (self, locale) {
+            (MoveCheckError::InvalidPuzzle(errors), _) => {
+                let messages: Vec<String> = errors.iter().map(|e| e.localize(locale)).collect();
+                messages.join("\n")
+            }
+            (MoveCheckError::GridMismatch, Locale::Ja) => {
+                "盤面が問題のサイズと一致しません".to_string()
+            }
+            (MoveCheckError::GridMismatch, Locale::En) => {
+                "The grid does not match the puzzle size".to_string()
+            }
+        }
+    }
+}
+
+#[cfg(feature = "desktop")]
+impl Localize for StatsError {
+    fn localize(&self, locale: Locale) -> String {
+        match (self, locale) {
+            (StatsError::Read(e), Locale::Ja) => format!("統計の読み込みに失敗しました: {}", e),
+            (StatsError::Read(e), Locale::En) => format!("Failed to read the statistics: {}", e),
+            (StatsError::Serialize(e), Locale::Ja) => format!("シリアライズに失敗しました: {}", e),
+            (StatsError::Serialize(e), Locale::En) => format!("Failed to serialize: {}", e),
+            (StatsError::Write(e), Locale::Ja) => format!("統計の記録に失敗しました: {}", e),
+            (StatsError::Write(e), Locale::En) => {
+                format!("Failed to record the statistics: {}", e)
+            }
+        }
+    }
+}
+
+impl Localize for SolveResult {
+    fn localize(&self, locale: Locale) -> String {
+        match (self, locale) {
+            (SolveResult::UniqueSolution { .. }, Locale::Ja) => {
+                "一意解が見つかりました".to_string()
+            }
+            (SolveResult::UniqueSolution { .. }, Locale::En) => {
+                "A unique solution was found".to_string()
+            }
+            (SolveResult::MultipleSolutions { .. }, Locale::Ja) => {
+                "この問題には複数の解が存在します".to_string()
+            }
+            (SolveResult::MultipleSolutions { .. }, Locale::En) => {
+                "This puzzle has multiple solutions".to_string()
+            }
+            (SolveResult::NoSolution { .. }, Locale::Ja) => {
+                "この問題には解が存在しません（矛盾しています）".to_string()
+            }
+            (SolveResult::NoSolution { .. }, Locale::En) => {
+                "This puzzle has no solution (the hints contradict each other)".to_string()
+            }
+            (SolveResult::Timeout { .. }, Locale::Ja) => {
+                "解答に時間がかかりすぎています。問題サイズを小さくするか、タイムアウト時間を延長してください。"
+                    .to_string()
+            }
+            (SolveResult::Timeout { .. }, Locale::En) => {
+                "Solving is taking too long. Try a smaller puzzle or a longer timeout.".to_string()
+            }
+            (SolveResult::BudgetExhausted { .. }, Locale::Ja) => {
+                "探索の上限に達したため、解答を打ち切りました".to_string()
+            }
+            (SolveResult::BudgetExhausted { .. }, Locale::En) => {
+                "The search budget ran out before the puzzle was solved".to_string()
+            }
+            (SolveResult::InvalidInput { errors, .. }, Locale::Ja) => {
+                let errors: Vec<String> = errors.iter().map(|e| e.localize(locale)).collect();
+                format!("入力が正しくありません: {}", errors.join("、"))
+            }
+            (SolveResult::InvalidInput { errors, .. }, Locale::En) => {
+                let errors: Vec<String> = errors.iter().map(|e| e.localize(locale)).collect();
+                format!("Invalid input: {}", errors.join("; "))
+            }
+        }
+    }
+}
+
+#[cfg(feature = "desktop")]
+impl Localize for CommandError {
+    fn localize(&self, locale: Locale) -> String {
+        match (self, locale) {
+            (CommandError::Internal(message), Locale::Ja) => {
+                format!("内部エラーが発生しました: {}", message)
+            }
+            (CommandError::Internal(message), Locale::En) => format!("Internal error: {}", message),
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+impl Localize for ServerError {
+    fn localize(&self, locale: Locale) -> String {
+        match (self, locale) {
+            (ServerError::NotFound(path), Locale::Ja) => format!("{} は存在しません", path),
+            (ServerError::NotFound(path), Locale::En) => format!("No endpoint at {}", path),
+            (ServerError::MethodNotAllowed, Locale::Ja) => "POSTで送信してください".to_string(),
+            (ServerError::MethodNotAllowed, Locale::En) => "Use POST".to_string(),
+            (ServerError::BodyTooLarge { limit }, Locale::Ja) => {
+                format!("リクエストが大きすぎます（上限{}バイト）", limit)
+            }
+            (ServerError::BodyTooLarge { limit }, Locale::En) => {
+                format!("The request is larger than {} bytes", limit)
+            }
+            (ServerError::BodyTimeout { timeout }, Locale::Ja) => format!(
+                "リクエストの本文を{}秒以内に受け取れませんでした",
+                timeout.as_secs_f64()
+            ),
+            (ServerError::BodyTimeout { timeout }, Locale::En) => format!(
+                "The request body did not arrive within {} seconds",
+                timeout.as_secs_f64()
+            ),
+            (ServerError::InvalidRequest(e), Locale::Ja) => format!("リクエストが不正です: {}", e),
+            (ServerError::InvalidRequest(e), Locale::En) => format!("Invalid request: {}", e),
+            (ServerError::InvalidPuzzle(errors), _) => {
+                let messages: Vec<String> = errors.iter().map(|e| e.localize(locale)).collect();
+                messages.join("\n")
+            }
+            (ServerError::File(error), _) => error.localize(locale),
+            (ServerError::Busy, Locale::Ja) => {
+                "処理中のリクエストが多すぎます。しばらくしてから再試行してください".to_string()
+            }
+            (ServerError::Busy, Locale::En) => {
+                "Too many requests in progress; try again later".to_string()
+            }
+            (ServerError::Internal(message), Locale::Ja) => {
+                format!("内部エラーが発生しました: {}", message)
+            }
+            (ServerError::Internal(message), Locale::En) => format!("Internal error: {}", message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::types::SolveStats;
+
+    #[test]
+    fn test_locale_serde() {
+        assert_eq!(serde_json::to_string(&Locale::En).unwrap(), "\"en\"");
+        let locale: Locale = serde_json::from_str("\"ja\"").unwrap();
+        assert_eq!(locale, Locale::Ja);
+    }
+
+    #[test]
+    fn test_validation_error_locales() {
+        let error = ValidationError::HintTooLong {
+            axis: Axis::Column,
+            index: 2,
+            needed: 7,
+            available: 5,
+        };
+        assert_eq!(
+            error.localize(Locale::Ja),
+            "列3のヒントは最低7マス必要ですが、高さは5です"
+        );
+        assert_eq!(
+            error.localize(Locale::En),
+            "Column 3 needs at least 7 cells, but the height is 5"
+        );
+        assert_eq!(error.to_string(), error.localize(Locale::Ja));
+    }
+
+    #[test]
+    fn test_file_error_locales() {
+        let error = FileError::InvalidJson("EOF".to_string());
+        assert_eq!(error.localize(Locale::Ja), "JSON形式が不正です: EOF");
+        assert_eq!(error.localize(Locale::En), "Invalid JSON: EOF");
+    }
+
+    #[test]
+    fn test_invalid_input_locales() {
+        let result = SolveResult::invalid_input(
+            vec![
+                ValidationError::HintTooLong {
+                    axis: Axis::Row,
+                    index: 0,
+                    needed: 4,
+                    available: 3,
+                },
+                ValidationError::ZeroInMultiHint {
+                    axis: Axis::Column,
+                    index: 1,
+                    position: 0,
+                },
+            ],
+            Locale::En,
+            SolveStats::default(),
+        );
+        let SolveResult::InvalidInput {
+            errors, messages, ..
+        } = &result
+        else {
+            panic!("{:?}", result);
+        };
+        assert_eq!(messages.len(), errors.len());
+        assert_eq!(
+            result.localize(Locale::En),
+            format!("Invalid input: {}; {}", messages[0], messages[1])
+        );
+        assert!(result.localize(Locale::Ja).starts_with("入力が正しくありません: "));
+    }
+}
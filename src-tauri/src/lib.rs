@@ -1,18 +1,80 @@
+#[cfg(feature = "desktop")]
+use tauri::Manager;
+
+#[cfg(feature = "desktop")]
 mod commands;
-mod puzzle;
-mod solver;
+mod ffi;
+#[cfg(feature = "desktop")]
+mod game;
+mod i18n;
+#[cfg(feature = "desktop")]
+mod library;
+pub mod play;
+pub mod puzzle;
+#[cfg(feature = "python")]
+mod python;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "desktop")]
+mod settings;
+pub mod solver;
+#[cfg(feature = "desktop")]
+mod stats;
+mod unwind;
 
+#[cfg(feature = "desktop")]
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
+        .setup(|app| {
+            let config_dir = app.path().app_config_dir()?;
+            app.manage(settings::SettingsState::load(
+                config_dir.join(settings::SETTINGS_FILE_NAME),
+            ));
+            let data_dir = app.path().app_data_dir()?;
+            app.manage(library::LibraryState::open(
+                data_dir.join(library::LIBRARY_DIR_NAME),
+            ));
+            app.manage(game::GameStore::new(data_dir.join(game::GAMES_DIR_NAME)));
+            app.manage(play::SolutionCache::default());
+            app.manage(stats::StatsLog::new(data_dir.join(stats::STATS_FILE_NAME)));
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             commands::solve_puzzle,
+            commands::solve_command,
             commands::generate_hints_command,
             commands::validate_puzzle_command,
+            commands::solve_status_message_command,
             commands::load_puzzle_file_command,
             commands::save_puzzle_file_command,
+            commands::check_solution_command,
+            commands::transform_puzzle_command,
+            commands::transform_grid_command,
+            commands::transform_solve_result_command,
+            commands::get_settings_command,
+            commands::update_settings_command,
+            commands::reset_settings_command,
+            commands::library_search_command,
+            commands::library_tags_command,
+            commands::library_load_command,
+            commands::library_add_command,
+            commands::library_duplicates_command,
+            commands::library_import_command,
+            commands::library_set_status_command,
+            commands::library_set_tags_command,
+            commands::library_remove_command,
+            commands::save_game_command,
+            commands::autosave_game_command,
+            commands::list_games_command,
+            commands::load_game_command,
+            commands::delete_game_command,
+            commands::check_moves_command,
+            commands::record_play_command,
+            commands::stats_summary_command,
+            commands::best_time_command,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -20,10 +82,21 @@ pub fn run() {
 
 #[cfg(test)]
 mod integration_tests {
-    use crate::solver::backtrack::backtrack_solve;
-    use crate::solver::types::CellState;
+    use crate::i18n::Locale;
+    use crate::puzzle::hint_generator::generate_hints;
+    use crate::solver::backtrack::{self, backtrack_solve, backtrack_solve_with_stats};
+    use crate::solver::options::SolverOptions;
+    use crate::solver::puzzle::Puzzle;
+    use crate::solver::types::{
+        Axis, CellState, LimitReached, SolveResult, SolveStats, SolverSettings, ValidationError,
+        GRID_SIZE_LIMIT,
+    };
     use crate::solver::validator::validate_puzzle;
 
+    fn puzzle(row_hints: &[Vec<u32>], col_hints: &[Vec<u32>]) -> Puzzle {
+        Puzzle::from_hints(row_hints.to_vec(), col_hints.to_vec()).unwrap()
+    }
+
     fn grid_to_u8(grid: &[Vec<CellState>]) -> Vec<Vec<u8>> {
         grid.iter()
             .map(|row| {
@@ -48,8 +121,9 @@ mod integration_tests {
         let row_hints = vec![vec![1], vec![1], vec![5], vec![1], vec![1]];
         let col_hints = vec![vec![1], vec![1], vec![5], vec![1], vec![1]];
         let grid = vec![vec![CellState::Unknown; 5]; 5];
-        let (solutions, timed_out) = backtrack_solve(&grid, &row_hints, &col_hints, 2, None);
-        assert!(!timed_out);
+        let (solutions, stopped) =
+            backtrack_solve(&grid, &puzzle(&row_hints, &col_hints), &SolverOptions::default());
+        assert_eq!(stopped, None);
         assert_eq!(solutions.len(), 1);
 
         let expected = vec![
@@ -71,8 +145,9 @@ mod integration_tests {
         let row_hints = vec![vec![1, 1], vec![1], vec![1, 1]];
         let col_hints = vec![vec![1, 1], vec![1], vec![1, 1]];
         let grid = vec![vec![CellState::Unknown; 3]; 3];
-        let (solutions, timed_out) = backtrack_solve(&grid, &row_hints, &col_hints, 2, None);
-        assert!(!timed_out);
+        let (solutions, stopped) =
+            backtrack_solve(&grid, &puzzle(&row_hints, &col_hints), &SolverOptions::default());
+        assert_eq!(stopped, None);
         assert_eq!(solutions.len(), 1);
 
         let expected = vec![vec![1, 0, 1], vec![0, 1, 0], vec![1, 0, 1]];
@@ -109,8 +184,9 @@ mod integration_tests {
             vec![1],
         ];
         let grid = vec![vec![CellState::Unknown; 10]; 10];
-        let (solutions, timed_out) = backtrack_solve(&grid, &row_hints, &col_hints, 2, None);
-        assert!(!timed_out);
+        let (solutions, stopped) =
+            backtrack_solve(&grid, &puzzle(&row_hints, &col_hints), &SolverOptions::default());
+        assert_eq!(stopped, None);
         assert_eq!(solutions.len(), 1);
 
         let expected = vec![
@@ -138,8 +214,9 @@ mod integration_tests {
         let row_hints = vec![vec![1], vec![1], vec![1], vec![4]];
         let col_hints = vec![vec![4], vec![1], vec![1], vec![1]];
         let grid = vec![vec![CellState::Unknown; 4]; 4];
-        let (solutions, timed_out) = backtrack_solve(&grid, &row_hints, &col_hints, 2, None);
-        assert!(!timed_out);
+        let (solutions, stopped) =
+            backtrack_solve(&grid, &puzzle(&row_hints, &col_hints), &SolverOptions::default());
+        assert_eq!(stopped, None);
         assert_eq!(solutions.len(), 1);
         let expected = vec![
             vec![1, 0, 0, 0],
@@ -162,8 +239,9 @@ mod integration_tests {
         let row_hints = vec![vec![6], vec![1, 1], vec![1, 1], vec![1, 1], vec![1, 1], vec![6]];
         let col_hints = vec![vec![6], vec![1, 1], vec![1, 1], vec![1, 1], vec![1, 1], vec![6]];
         let grid = vec![vec![CellState::Unknown; 6]; 6];
-        let (solutions, timed_out) = backtrack_solve(&grid, &row_hints, &col_hints, 2, None);
-        assert!(!timed_out);
+        let (solutions, stopped) =
+            backtrack_solve(&grid, &puzzle(&row_hints, &col_hints), &SolverOptions::default());
+        assert_eq!(stopped, None);
         assert_eq!(solutions.len(), 1);
     }
 
@@ -173,8 +251,9 @@ mod integration_tests {
         let row_hints = vec![vec![5]; 5];
         let col_hints = vec![vec![1]; 5];
         let grid = vec![vec![CellState::Unknown; 5]; 5];
-        let (solutions, timed_out) = backtrack_solve(&grid, &row_hints, &col_hints, 2, None);
-        assert!(!timed_out);
+        let (solutions, stopped) =
+            backtrack_solve(&grid, &puzzle(&row_hints, &col_hints), &SolverOptions::default());
+        assert_eq!(stopped, None);
         assert_eq!(solutions.len(), 0);
     }
 
@@ -187,17 +266,79 @@ mod integration_tests {
         let row_hints = vec![vec![1]; 2];
         let col_hints = vec![vec![1]; 2];
         let grid = vec![vec![CellState::Unknown; 2]; 2];
-        let (solutions, timed_out) = backtrack_solve(&grid, &row_hints, &col_hints, 2, None);
-        assert!(!timed_out);
+        let (solutions, stopped) =
+            backtrack_solve(&grid, &puzzle(&row_hints, &col_hints), &SolverOptions::default());
+        assert_eq!(stopped, None);
         assert_eq!(solutions.len(), 2, "Expected 2 solutions for 2x2 with [1],[1]");
     }
 
+    // Shapes that disagree with the hints are a caller's mistake, not a crash
+    #[test]
+    fn test_mismatched_grid_has_no_solution() {
+        let hints = vec![vec![1]; 2];
+        let shapes = [
+            vec![vec![CellState::Unknown; 2]; 3],
+            vec![vec![CellState::Unknown; 3]; 2],
+            vec![vec![CellState::Unknown; 2], vec![CellState::Unknown]],
+        ];
+        for grid in shapes {
+            let (solutions, stopped) =
+                backtrack_solve(&grid, &puzzle(&hints, &hints), &SolverOptions::default());
+            assert!(solutions.is_empty() && stopped.is_none(), "{:?}", grid);
+        }
+    }
+
+    #[test]
+    fn test_search_node_count() {
+        // Solved by deduction alone: only the root node
+        let hints = vec![vec![1], vec![1], vec![5], vec![1], vec![1]];
+        let grid = vec![vec![CellState::Unknown; 5]; 5];
+        let options = SolverOptions::default();
+        let (_, _, stats) = backtrack_solve_with_stats(&grid, &puzzle(&hints, &hints), &options);
+        assert_eq!(stats.nodes, 1);
+        assert_eq!(stats.max_depth, 0);
+        assert_eq!((stats.cells_by_logic, stats.cells_by_search), (25, 0));
+
+        // Root, then one node for each guess at the first cell
+        let hints = vec![vec![1]; 2];
+        let grid = vec![vec![CellState::Unknown; 2]; 2];
+        let (solutions, _, stats) = backtrack_solve_with_stats(&grid, &puzzle(&hints, &hints), &options);
+        assert_eq!(solutions.len(), 2);
+        assert_eq!(stats.nodes, 3);
+        assert_eq!(stats.max_depth, 1);
+        // Nothing follows from the hints alone; one guess settles all four cells
+        assert_eq!((stats.cells_by_logic, stats.cells_by_search), (0, 4));
+        // Every row and column once at the root, twice after each guess
+        assert_eq!(stats.line_solver_calls, 4 + 8 + 8);
+    }
+
+    #[test]
+    fn test_solve_result_stats_serde() {
+        let result = SolveResult::NoSolution {
+            stats: SolveStats {
+                elapsed_seconds: 0.5,
+                nodes: 3,
+                ..SolveStats::default()
+            },
+        };
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["type"], "NoSolution");
+        assert_eq!(json["stats"]["elapsedSeconds"], 0.5);
+        assert_eq!(json["stats"]["lineSolverCalls"], 0);
+
+        // Results sent back by the frontend may leave the statistics out
+        let parsed: SolveResult =
+            serde_json::from_str(r#"{"type": "UniqueSolution", "grid": [[1]]}"#).unwrap();
+        assert_eq!(parsed.stats(), &SolveStats::default());
+    }
+
     #[test]
     fn test_edge_1x1() {
         let row_hints = vec![vec![1]];
         let col_hints = vec![vec![1]];
         let grid = vec![vec![CellState::Unknown; 1]];
-        let (solutions, _) = backtrack_solve(&grid, &row_hints, &col_hints, 2, None);
+        let (solutions, _) =
+            backtrack_solve(&grid, &puzzle(&row_hints, &col_hints), &SolverOptions::default());
         assert_eq!(solutions.len(), 1);
         assert_eq!(solutions[0][0][0], CellState::Filled);
     }
@@ -207,7 +348,8 @@ mod integration_tests {
         let row_hints: Vec<Vec<u32>> = vec![vec![]; 5];
         let col_hints: Vec<Vec<u32>> = vec![vec![]; 5];
         let grid = vec![vec![CellState::Unknown; 5]; 5];
-        let (solutions, _) = backtrack_solve(&grid, &row_hints, &col_hints, 2, None);
+        let (solutions, _) =
+            backtrack_solve(&grid, &puzzle(&row_hints, &col_hints), &SolverOptions::default());
         assert_eq!(solutions.len(), 1);
         let result = grid_to_u8(&solutions[0]);
         assert!(result.iter().all(|row| row.iter().all(|&c| c == 0)));
@@ -218,7 +360,8 @@ mod integration_tests {
         let row_hints = vec![vec![5]; 5];
         let col_hints = vec![vec![5]; 5];
         let grid = vec![vec![CellState::Unknown; 5]; 5];
-        let (solutions, _) = backtrack_solve(&grid, &row_hints, &col_hints, 2, None);
+        let (solutions, _) =
+            backtrack_solve(&grid, &puzzle(&row_hints, &col_hints), &SolverOptions::default());
         assert_eq!(solutions.len(), 1);
         let result = grid_to_u8(&solutions[0]);
         assert!(result.iter().all(|row| row.iter().all(|&c| c == 1)));
@@ -229,7 +372,7 @@ mod integration_tests {
         // Cross pattern: row_sum = col_sum = 9
         let row_hints = vec![vec![1], vec![1], vec![5], vec![1], vec![1]];
         let col_hints = vec![vec![1], vec![1], vec![5], vec![1], vec![1]];
-        let result = validate_puzzle(5, 5, &row_hints, &col_hints);
+        let result = validate_puzzle(5, 5, &row_hints, &col_hints, &SolverSettings::default());
         assert!(result.valid, "Errors: {:?}", result.errors);
         assert!(result.errors.is_empty());
     }
@@ -238,17 +381,134 @@ mod integration_tests {
     fn test_validator_sum_mismatch() {
         let row_hints = vec![vec![5]; 5]; // total: 25
         let col_hints = vec![vec![1]; 5]; // total: 5
-        let result = validate_puzzle(5, 5, &row_hints, &col_hints);
+        let result = validate_puzzle(5, 5, &row_hints, &col_hints, &SolverSettings::default());
         assert!(!result.valid);
-        assert!(result.errors.iter().any(|e| e.contains("一致しません")));
+        assert!(result.errors.contains(&ValidationError::SumMismatch {
+            row_total: 25,
+            col_total: 5,
+        }));
     }
 
     #[test]
     fn test_validator_hint_too_large() {
         let row_hints = vec![vec![6]]; // needs 6 but width is 5
         let col_hints = vec![vec![1]; 5];
-        let result = validate_puzzle(5, 1, &row_hints, &col_hints);
+        let result = validate_puzzle(5, 1, &row_hints, &col_hints, &SolverSettings::default());
+        assert!(!result.valid);
+        assert!(result.errors.contains(&ValidationError::HintTooLong {
+            axis: Axis::Row,
+            index: 0,
+            needed: 6,
+            available: 5,
+        }));
+    }
+
+    #[test]
+    fn test_validator_zero_in_multi_hint() {
+        let row_hints = vec![vec![1, 0, 1], vec![], vec![]];
+        let col_hints = vec![vec![1], vec![], vec![], vec![], vec![1]];
+        let result = validate_puzzle(5, 3, &row_hints, &col_hints, &SolverSettings::default());
+        assert_eq!(
+            result.errors,
+            vec![ValidationError::ZeroInMultiHint {
+                axis: Axis::Row,
+                index: 0,
+                position: 1,
+            }]
+        );
+        assert_eq!(
+            result.errors[0].to_string(),
+            "行1のヒント位置2に0が含まれています"
+        );
+    }
+
+    // Passes the static checks, but row 0 sees ■ □ ■ from its columns
+    // and cannot place a block of 2 there
+    #[test]
+    fn test_validator_line_contradiction() {
+        let row_hints = vec![vec![2], vec![1, 1], vec![1, 1]];
+        let col_hints = vec![vec![3], vec![], vec![3]];
+        let result = validate_puzzle(3, 3, &row_hints, &col_hints, &SolverSettings::default());
+        assert!(!result.valid);
+        assert_eq!(
+            result.errors,
+            vec![
+                ValidationError::LineContradiction {
+                    axis: Axis::Row,
+                    index: 0,
+                },
+                ValidationError::FillCountExceeded {
+                    axis: Axis::Column,
+                    index: 1,
+                    needed: 0,
+                    forced: 1,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validator_fill_count_unreachable() {
+        // Rows 1 and 2 are empty, so column 0 can hold at most one filled cell
+        let row_hints = vec![vec![3], vec![], vec![]];
+        let col_hints = vec![vec![2], vec![1], vec![]];
+        let result = validate_puzzle(3, 3, &row_hints, &col_hints, &SolverSettings::default());
+        assert!(result.errors.contains(&ValidationError::FillCountUnreachable {
+            axis: Axis::Column,
+            index: 0,
+            needed: 2,
+            possible: 1,
+        }));
+    }
+
+    /// Deterministic picture with about three quarters of the cells filled.
+    fn dense_picture(width: usize, height: usize) -> Vec<Vec<u8>> {
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        (0..height)
+            .map(|_| {
+                (0..width)
+                    .map(|_| {
+                        state = state
+                            .wrapping_mul(6364136223846793005)
+                            .wrapping_add(1442695040888963407);
+                        u8::from((state >> 33) % 4 != 0)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    // 150 wide x 120 tall, beyond the default size limit
+    #[test]
+    fn test_large_grid_150x120() {
+        let picture = dense_picture(150, 120);
+        let (row_hints, col_hints) = generate_hints(&picture).unwrap();
+
+        let result = validate_puzzle(150, 120, &row_hints, &col_hints, &SolverSettings::default());
         assert!(!result.valid);
+        let settings = SolverSettings { max_grid_size: 150 };
+        let result = validate_puzzle(150, 120, &row_hints, &col_hints, &settings);
+        assert!(result.valid, "Errors: {:?}", result.errors);
+
+        let grid = vec![vec![CellState::Unknown; 150]; 120];
+        let (solutions, stopped) =
+            backtrack_solve(&grid, &puzzle(&row_hints, &col_hints), &SolverOptions::default());
+        assert_eq!(stopped, None);
+        assert!(solutions.iter().any(|s| grid_to_u8(s) == picture));
+    }
+
+    #[test]
+    fn test_validator_caps_requested_size() {
+        // A huge limit from the caller must not allow a grid that cannot be allocated
+        let settings = SolverSettings {
+            max_grid_size: usize::MAX,
+        };
+        let side = 1 << 40;
+        let result = validate_puzzle(side, side, &[], &[], &settings);
+        assert!(result.errors.iter().any(|e| matches!(
+            e,
+            ValidationError::DimensionOutOfRange { max, .. } if *max == GRID_SIZE_LIMIT
+        )));
     }
 
     #[test]
@@ -260,7 +520,101 @@ mod integration_tests {
         let row_hints = vec![vec![2]; 10];
         let col_hints = vec![vec![2]; 10];
         let grid = vec![vec![CellState::Unknown; 10]; 10];
-        let (_, timed_out) = backtrack_solve(&grid, &row_hints, &col_hints, 2, Some(deadline));
-        assert!(timed_out);
+        let options = SolverOptions::new().deadline(deadline);
+        let (_, stopped) = backtrack_solve(&grid, &puzzle(&row_hints, &col_hints), &options);
+        assert_eq!(stopped, Some(LimitReached::Deadline));
+    }
+
+    #[test]
+    fn test_search_budget() {
+        use crate::solver::types::SearchBudget;
+        let hints = vec![vec![1]; 2];
+        let grid = vec![vec![CellState::Unknown; 2]; 2];
+        let solve = |budget: SearchBudget| {
+            let options = SolverOptions::new().budget(budget);
+            backtrack_solve_with_stats(&grid, &puzzle(&hints, &hints), &options)
+        };
+
+        // The root and the first guess find one of the two solutions
+        let (solutions, stopped, stats) = solve(SearchBudget {
+            max_nodes: Some(2),
+            ..SearchBudget::default()
+        });
+        assert_eq!(solutions.len(), 1);
+        assert_eq!(stopped, Some(LimitReached::Budget));
+        assert_eq!(stats.nodes, 2);
+
+        // The root's four line-solver calls use up the budget before any guess
+        let (solutions, stopped, stats) = solve(SearchBudget {
+            max_line_solver_calls: Some(4),
+            ..SearchBudget::default()
+        });
+        assert!(solutions.is_empty());
+        assert_eq!(stopped, Some(LimitReached::Budget));
+        assert_eq!(stats.line_solver_calls, 4);
+
+        let (solutions, stopped, _) = solve(SearchBudget {
+            max_nodes: Some(3),
+            max_line_solver_calls: Some(100),
+        });
+        assert_eq!((solutions.len(), stopped), (2, None));
+    }
+
+    // One solution found before the limit says nothing about uniqueness
+    #[test]
+    fn test_limit_after_one_solution_is_undecided() {
+        let hints = vec![vec![1]; 2];
+        let two_solutions = puzzle(&hints, &hints);
+        let result = backtrack::solve(&two_solutions, &SolverOptions::new().max_nodes(2));
+        assert!(
+            matches!(result, SolveResult::BudgetExhausted { .. }),
+            "{:?}",
+            result
+        );
+
+        // Nor when asking for a single solution, which is never proof of uniqueness
+        let options = SolverOptions::new().max_solutions(1).max_nodes(2);
+        let result = backtrack::solve(&two_solutions, &options);
+        assert!(
+            matches!(result, SolveResult::BudgetExhausted { .. }),
+            "{:?}",
+            result
+        );
+        let result = backtrack::solve(&two_solutions, &SolverOptions::new().max_solutions(1));
+        assert!(
+            matches!(result, SolveResult::MultipleSolutions { .. }),
+            "{:?}",
+            result
+        );
+
+        // A unique puzzle solved within the budget is still reported as such
+        let cross = vec![vec![1], vec![3], vec![1]];
+        let result = backtrack::solve(&puzzle(&cross, &cross), &SolverOptions::new().max_nodes(2));
+        assert!(
+            matches!(result, SolveResult::UniqueSolution { .. }),
+            "{:?}",
+            result
+        );
+    }
+
+    #[test]
+    fn test_solve_hints_rejects_before_solving() {
+        let settings = SolverSettings { max_grid_size: 2 };
+        let cross = vec![vec![1], vec![3], vec![1]];
+        let options = SolverOptions::new();
+        let result = backtrack::solve_hints(&cross, &cross, &options, &settings, Locale::En);
+        let SolveResult::InvalidInput {
+            errors, messages, ..
+        } = result
+        else {
+            panic!("expected InvalidInput, got {:?}", result);
+        };
+        assert_eq!(errors.len(), 2); // Width and height
+        assert_eq!(messages.len(), 2);
+
+        let hints = vec![vec![1]; 2];
+        let options = SolverOptions::new();
+        let result = backtrack::solve_hints(&hints, &hints, &options, &settings, Locale::En);
+        assert!(matches!(result, SolveResult::MultipleSolutions { .. }));
     }
 }
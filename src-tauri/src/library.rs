@@ -0,0 +1,795 @@
+use std::cmp::Ordering;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::{Locale, Localize};
+use crate::puzzle::data::PuzzleData;
+use crate::puzzle::file_io::{self, FileError};
+use crate::puzzle::fingerprint::Fingerprint;
+
+pub const LIBRARY_DIR_NAME: &str = "library";
+const INDEX_FILE_NAME: &str = "index.json";
+
+/// Schema version of the index file written by this build.
+/// Version 2 adds fingerprints; entries from version 1 get them on open.
+pub const INDEX_VERSION: u32 = 2;
+
+/// Puzzles copied into a new library, keyed by the id they get there.
+const STARTER_SET: [(&str, &str); 10] = [
+    ("easy_5x5", include_str!("../../docs/samples/easy_5x5.json")),
+    ("edge_1x1", include_str!("../../docs/samples/edge_1x1.json")),
+    (
+        "edge_all_empty_5x5",
+        include_str!("../../docs/samples/edge_all_empty_5x5.json"),
+    ),
+    (
+        "edge_all_filled_5x5",
+        include_str!("../../docs/samples/edge_all_filled_5x5.json"),
+    ),
+    (
+        "medium_10x10",
+        include_str!("../../docs/samples/medium_10x10.json"),
+    ),
+    (
+        "heart_15x15",
+        include_str!("../../docs/samples/heart_15x15.json"),
+    ),
+    (
+        "backtrack_required_10x10",
+        include_str!("../../docs/samples/backtrack_required_10x10.json"),
+    ),
+    (
+        "edge_large_50x50",
+        include_str!("../../docs/samples/edge_large_50x50.json"),
+    ),
+    (
+        "multiple_solutions",
+        include_str!("../../docs/samples/multiple_solutions.json"),
+    ),
+    (
+        "no_solution",
+        include_str!("../../docs/samples/no_solution.json"),
+    ),
+];
+
+/// Tag given to every puzzle of the starter set.
+const STARTER_TAG: &str = "sample";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SolveStatus {
+    Unsolved,
+    InProgress,
+    Solved,
+}
+
+/// What the index knows about one puzzle file, so that searching never has
+/// to open the puzzles themselves.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryEntry {
+    /// File stem of the puzzle inside the library folder.
+    pub id: String,
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub width: usize,
+    pub height: usize,
+    pub difficulty: Option<String>,
+    pub status: SolveStatus,
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub fingerprint: Fingerprint,
+}
+
+impl LibraryEntry {
+    fn new(id: String, puzzle: &PuzzleData, tags: Vec<String>) -> Self {
+        let metadata = puzzle.metadata.as_ref();
+        LibraryEntry {
+            id,
+            title: metadata.and_then(|m| m.title.clone()),
+            author: metadata.and_then(|m| m.author.clone()),
+            width: puzzle.width,
+            height: puzzle.height,
+            difficulty: metadata.and_then(|m| m.difficulty.clone()),
+            status: SolveStatus::Unsolved,
+            tags,
+            fingerprint: Fingerprint::new(&puzzle.row_hints, &puzzle.col_hints),
+        }
+    }
+}
+
+/// Entries are kept in the order the puzzles were added.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LibraryIndex {
+    version: u32,
+    entries: Vec<LibraryEntry>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SortKey {
+    #[default]
+    Added,
+    Title,
+    Size,
+    Difficulty,
+    Status,
+}
+
+/// Search, filter, sort and paging options. Every filter is optional;
+/// an empty query returns the first page of the whole library.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LibraryQuery {
+    /// Case-insensitive substring of the title, author or id.
+    pub text: Option<String>,
+    pub difficulty: Option<String>,
+    pub status: Option<SolveStatus>,
+    /// Entries must carry every one of these tags.
+    pub tags: Vec<String>,
+    /// Bounds on the larger of width and height.
+    pub min_size: Option<usize>,
+    pub max_size: Option<usize>,
+    pub sort: SortKey,
+    pub descending: bool,
+    /// Zero-based page number.
+    pub page: usize,
+    pub page_size: usize,
+}
+
+impl Default for LibraryQuery {
+    fn default() -> Self {
+        LibraryQuery {
+            text: None,
+            difficulty: None,
+            status: None,
+            tags: Vec::new(),
+            min_size: None,
+            max_size: None,
+            sort: SortKey::Added,
+            descending: false,
+            page: 0,
+            page_size: 20,
+        }
+    }
+}
+
+impl LibraryQuery {
+    fn matches(&self, entry: &LibraryEntry) -> bool {
+        let text_matches = self.text.as_ref().map_or(true, |text| {
+            let text = text.to_lowercase();
+            [Some(&entry.id), entry.title.as_ref(), entry.author.as_ref()]
+                .into_iter()
+                .flatten()
+                .any(|field| field.to_lowercase().contains(&text))
+        });
+        let size = entry.width.max(entry.height);
+        text_matches
+            && self
+                .difficulty
+                .as_ref()
+                .map_or(true, |d| entry.difficulty.as_ref() == Some(d))
+            && self.status.map_or(true, |s| entry.status == s)
+            && self.tags.iter().all(|tag| entry.tags.contains(tag))
+            && self.min_size.map_or(true, |min| size >= min)
+            && self.max_size.map_or(true, |max| size <= max)
+    }
+
+    fn compare(&self, a: &LibraryEntry, b: &LibraryEntry) -> Ordering {
+        let ordering = match self.sort {
+            // The index is already in the order puzzles were added
+            SortKey::Added => Ordering::Equal,
+            // Puzzles without a title come last
+            SortKey::Title => (a.title.is_none(), &a.title).cmp(&(b.title.is_none(), &b.title)),
+            SortKey::Size => (a.width * a.height).cmp(&(b.width * b.height)),
+            SortKey::Difficulty => difficulty_rank(a).cmp(&difficulty_rank(b)),
+            SortKey::Status => status_rank(a.status).cmp(&status_rank(b.status)),
+        };
+        if self.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+}
+
+/// Known difficulties in increasing order, then unknown ones, then none.
+fn difficulty_rank(entry: &LibraryEntry) -> u8 {
+    match entry.difficulty.as_deref() {
+        Some("easy") => 0,
+        Some("medium") => 1,
+        Some("hard") => 2,
+        Some(_) => 3,
+        None => 4,
+    }
+}
+
+fn status_rank(status: SolveStatus) -> u8 {
+    match status {
+        SolveStatus::Unsolved => 0,
+        SolveStatus::InProgress => 1,
+        SolveStatus::Solved => 2,
+    }
+}
+
+/// One page of search results; `total` counts every match across all pages.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryPage {
+    pub entries: Vec<LibraryEntry>,
+    pub total: usize,
+    pub page: usize,
+    pub page_size: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DuplicateKind {
+    /// Same hints in the same orientation.
+    Exact,
+    /// Same hints once flipped, rotated or transposed.
+    Symmetric,
+}
+
+/// A library puzzle that a new puzzle duplicates.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Duplicate {
+    pub id: String,
+    pub title: Option<String>,
+    pub kind: DuplicateKind,
+}
+
+/// A newly added entry, with the puzzles it duplicates as a warning.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddedPuzzle {
+    pub entry: LibraryEntry,
+    pub duplicates: Vec<Duplicate>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedImport {
+    pub path: String,
+    pub duplicates: Vec<Duplicate>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FailedImport {
+    pub path: String,
+    pub message: String,
+}
+
+/// What happened to each file of an imported pack.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    pub added: Vec<AddedPuzzle>,
+    /// Files left out because they duplicate a library puzzle.
+    pub skipped: Vec<SkippedImport>,
+    pub failed: Vec<FailedImport>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LibraryError {
+    Read(String),
+    InvalidJson(String),
+    Serialize(String),
+    Write(String),
+    /// No entry has this id.
+    NotFound(String),
+    /// The index was written by a newer version of the app.
+    UnsupportedVersion { found: u32, supported: u32 },
+    /// A puzzle file could not be read or written.
+    Puzzle(FileError),
+}
+
+/// A folder of puzzle files plus an index describing them.
+pub struct Library {
+    dir: PathBuf,
+    index: LibraryIndex,
+}
+
+impl Library {
+    /// Open the library in `dir`. A new library is filled with the starter set;
+    /// a folder whose index is missing is re-indexed from its puzzle files,
+    /// which resets their status and tags. An index that cannot be read is an
+    /// error and is left as it is, so that no tags or status are lost.
+    pub fn open(dir: &Path) -> Result<Self, LibraryError> {
+        fs::create_dir_all(dir).map_err(|e| LibraryError::Write(e.to_string()))?;
+        let mut index = match read_index(&dir.join(INDEX_FILE_NAME))? {
+            Some(index) => index,
+            None => {
+                let entries = scan_puzzles(dir)?;
+                if entries.is_empty() {
+                    install_starter_set(dir)?
+                } else {
+                    entries
+                }
+            }
+        };
+        for entry in index.iter_mut().filter(|e| e.fingerprint.exact.is_empty()) {
+            if let Ok(puzzle) = file_io::load_puzzle_file(&dir.join(format!("{}.json", entry.id))) {
+                entry.fingerprint = Fingerprint::new(&puzzle.row_hints, &puzzle.col_hints);
+            }
+        }
+        let library = Library {
+            dir: dir.to_path_buf(),
+            index: LibraryIndex {
+                version: INDEX_VERSION,
+                entries: index,
+            },
+        };
+        library.save_index()?;
+        Ok(library)
+    }
+
+    pub fn search(&self, query: &LibraryQuery) -> LibraryPage {
+        let mut matches: Vec<&LibraryEntry> = self
+            .index
+            .entries
+            .iter()
+            .filter(|e| query.matches(e))
+            .collect();
+        // Stable, so ties keep the order puzzles were added
+        matches.sort_by(|a, b| query.compare(a, b));
+
+        let page_size = query.page_size.max(1);
+        let entries = matches
+            .iter()
+            .skip(query.page.saturating_mul(page_size))
+            .take(page_size)
+            .map(|e| (*e).clone())
+            .collect();
+        LibraryPage {
+            entries,
+            total: matches.len(),
+            page: query.page,
+            page_size,
+        }
+    }
+
+    /// Every tag in use, sorted and without duplicates.
+    pub fn tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = self
+            .index
+            .entries
+            .iter()
+            .flat_map(|e| e.tags.iter().cloned())
+            .collect();
+        tags.sort();
+        tags.dedup();
+        tags
+    }
+
+    pub fn load(&self, id: &str) -> Result<PuzzleData, LibraryError> {
+        self.entry(id)?;
+        file_io::load_puzzle_file(&self.puzzle_path(id)).map_err(LibraryError::Puzzle)
+    }
+
+    /// Library puzzles with the same hints, exact matches first.
+    pub fn duplicates_of(&self, puzzle: &PuzzleData) -> Vec<Duplicate> {
+        let fingerprint = Fingerprint::new(&puzzle.row_hints, &puzzle.col_hints);
+        let mut duplicates: Vec<Duplicate> = self
+            .index
+            .entries
+            .iter()
+            .filter_map(|e| {
+                let kind = if e.fingerprint.exact == fingerprint.exact {
+                    DuplicateKind::Exact
+                } else if e.fingerprint.symmetric == fingerprint.symmetric {
+                    DuplicateKind::Symmetric
+                } else {
+                    return None;
+                };
+                Some(Duplicate {
+                    id: e.id.clone(),
+                    title: e.title.clone(),
+                    kind,
+                })
+            })
+            .collect();
+        duplicates.sort_by_key(|d| d.kind != DuplicateKind::Exact);
+        duplicates
+    }
+
+    /// Copy a puzzle into the library under a fresh id.
+    /// Duplicates are added all the same; the caller decides whether to warn.
+    pub fn add(
+        &mut self,
+        puzzle: &PuzzleData,
+        tags: Vec<String>,
+    ) -> Result<AddedPuzzle, LibraryError> {
+        let duplicates = self.duplicates_of(puzzle);
+        let id = (self.index.entries.len() + 1..)
+            .map(|n| format!("puzzle-{}", n))
+            .find(|id| self.entry(id).is_err() && !self.puzzle_path(id).exists())
+            .unwrap();
+        file_io::save_puzzle_file(&self.puzzle_path(&id), puzzle).map_err(LibraryError::Puzzle)?;
+        let entry = LibraryEntry::new(id, puzzle, tags);
+        self.index.entries.push(entry.clone());
+        self.save_index()?;
+        Ok(AddedPuzzle { entry, duplicates })
+    }
+
+    /// Add a pack of puzzle files, optionally leaving out duplicates, which
+    /// includes files duplicating an earlier file of the same pack.
+    /// Unreadable files are reported in `locale` and do not stop the import.
+    pub fn import(
+        &mut self,
+        paths: &[PathBuf],
+        tags: &[String],
+        skip_duplicates: bool,
+        locale: Locale,
+    ) -> Result<ImportReport, LibraryError> {
+        let mut report = ImportReport::default();
+        for path in paths {
+            let display = path.display().to_string();
+            let puzzle = match file_io::load_puzzle_file(path) {
+                Ok(puzzle) => puzzle,
+                Err(e) => {
+                    report.failed.push(FailedImport {
+                        path: display,
+                        message: e.localize(locale),
+                    });
+                    continue;
+                }
+            };
+            let duplicates = self.duplicates_of(&puzzle);
+            if skip_duplicates && !duplicates.is_empty() {
+                report.skipped.push(SkippedImport {
+                    path: display,
+                    duplicates,
+                });
+                continue;
+            }
+            match self.add(&puzzle, tags.to_vec()) {
+                Ok(added) => report.added.push(added),
+                // Only the puzzle itself could not be written; carry on
+                Err(LibraryError::Puzzle(e)) => report.failed.push(FailedImport {
+                    path: display,
+                    message: e.localize(locale),
+                }),
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(report)
+    }
+
+    pub fn set_status(
+        &mut self,
+        id: &str,
+        status: SolveStatus,
+    ) -> Result<LibraryEntry, LibraryError> {
+        self.update_entry(id, |entry| entry.status = status)
+    }
+
+    pub fn set_tags(&mut self, id: &str, tags: Vec<String>) -> Result<LibraryEntry, LibraryError> {
+        self.update_entry(id, |entry| entry.tags = tags)
+    }
+
+    /// Remove a puzzle and its file.
+    pub fn remove(&mut self, id: &str) -> Result<(), LibraryError> {
+        self.entry(id)?;
+        let path = self.puzzle_path(id);
+        if path.exists() {
+            fs::remove_file(path).map_err(|e| LibraryError::Write(e.to_string()))?;
+        }
+        self.index.entries.retain(|e| e.id != id);
+        self.save_index()
+    }
+
+    fn entry(&self, id: &str) -> Result<&LibraryEntry, LibraryError> {
+        self.index
+            .entries
+            .iter()
+            .find(|e| e.id == id)
+            .ok_or_else(|| LibraryError::NotFound(id.to_string()))
+    }
+
+    fn update_entry(
+        &mut self,
+        id: &str,
+        update: impl FnOnce(&mut LibraryEntry),
+    ) -> Result<LibraryEntry, LibraryError> {
+        let entry = self
+            .index
+            .entries
+            .iter_mut()
+            .find(|e| e.id == id)
+            .ok_or_else(|| LibraryError::NotFound(id.to_string()))?;
+        update(entry);
+        let entry = entry.clone();
+        self.save_index()?;
+        Ok(entry)
+    }
+
+    fn puzzle_path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", id))
+    }
+
+    fn save_index(&self) -> Result<(), LibraryError> {
+        let json = serde_json::to_string_pretty(&self.index)
+            .map_err(|e| LibraryError::Serialize(e.to_string()))?;
+        fs::write(self.dir.join(INDEX_FILE_NAME), json)
+            .map_err(|e| LibraryError::Write(e.to_string()))
+    }
+}
+
+/// The entries of the index at `path`, or `None` if there is no index yet.
+fn read_index(path: &Path) -> Result<Option<Vec<LibraryEntry>>, LibraryError> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(LibraryError::Read(e.to_string())),
+    };
+    let index: LibraryIndex =
+        serde_json::from_str(&content).map_err(|e| LibraryError::InvalidJson(e.to_string()))?;
+    if index.version > INDEX_VERSION {
+        return Err(LibraryError::UnsupportedVersion {
+            found: index.version,
+            supported: INDEX_VERSION,
+        });
+    }
+    Ok(Some(index.entries))
+}
+
+/// Index every puzzle file in `dir`, in id order; other files are ignored.
+fn scan_puzzles(dir: &Path) -> Result<Vec<LibraryEntry>, LibraryError> {
+    let mut entries = Vec::new();
+    for item in fs::read_dir(dir).map_err(|e| LibraryError::Read(e.to_string()))? {
+        let path = item.map_err(|e| LibraryError::Read(e.to_string()))?.path();
+        let is_puzzle = path.extension().is_some_and(|ext| ext == "json")
+            && path.file_name().is_some_and(|name| name != INDEX_FILE_NAME);
+        if !is_puzzle {
+            continue;
+        }
+        let (Some(id), Ok(puzzle)) = (
+            path.file_stem().and_then(|s| s.to_str()),
+            file_io::load_puzzle_file(&path),
+        ) else {
+            continue;
+        };
+        entries.push(LibraryEntry::new(id.to_string(), &puzzle, Vec::new()));
+    }
+    entries.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(entries)
+}
+
+fn install_starter_set(dir: &Path) -> Result<Vec<LibraryEntry>, LibraryError> {
+    let mut entries = Vec::new();
+    for (id, content) in STARTER_SET {
+        let puzzle = file_io::parse_puzzle(content).map_err(LibraryError::Puzzle)?;
+        file_io::save_puzzle_file(&dir.join(format!("{}.json", id)), &puzzle)
+            .map_err(LibraryError::Puzzle)?;
+        entries.push(LibraryEntry::new(
+            id.to_string(),
+            &puzzle,
+            vec![STARTER_TAG.to_string()],
+        ));
+    }
+    Ok(entries)
+}
+
+/// The library shared between commands. If the folder could not be opened,
+/// the error is kept and every command reports it.
+pub struct LibraryState {
+    library: Mutex<Result<Library, LibraryError>>,
+}
+
+impl LibraryState {
+    pub fn open(dir: PathBuf) -> Self {
+        LibraryState {
+            library: Mutex::new(Library::open(&dir)),
+        }
+    }
+
+    /// Run `f` on the library, or return the error that kept it from opening.
+    pub fn with<T>(
+        &self,
+        f: impl FnOnce(&mut Library) -> Result<T, LibraryError>,
+    ) -> Result<T, LibraryError> {
+        match &mut *self.library.lock().unwrap_or_else(PoisonError::into_inner) {
+            Ok(library) => f(library),
+            Err(e) => Err(e.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_library_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "illust-logic-library-{}-{}",
+            std::process::id(),
+            name
+        ))
+    }
+
+    #[test]
+    fn test_new_library_has_starter_set() {
+        let dir = temp_library_dir("starter");
+        let library = Library::open(&dir).unwrap();
+        let page = library.search(&LibraryQuery::default());
+        assert_eq!(page.total, STARTER_SET.len());
+        assert_eq!(page.entries[0].id, "easy_5x5");
+        assert_eq!(page.entries[0].title.as_deref(), Some("簡単な5x5問題"));
+        assert_eq!(library.tags(), vec![STARTER_TAG.to_string()]);
+        assert!(library.load("edge_1x1").unwrap().solution.is_some());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_search_filter_sort_and_page() {
+        let dir = temp_library_dir("search");
+        let library = Library::open(&dir).unwrap();
+
+        let easy = library.search(&LibraryQuery {
+            difficulty: Some("easy".to_string()),
+            sort: SortKey::Size,
+            ..LibraryQuery::default()
+        });
+        assert_eq!(easy.total, 4);
+        assert_eq!(easy.entries[0].id, "edge_1x1");
+
+        let text = library.search(&LibraryQuery {
+            text: Some("ハート".to_string()),
+            ..LibraryQuery::default()
+        });
+        assert_eq!(text.entries.len(), 1);
+        assert_eq!(text.entries[0].id, "heart_15x15");
+
+        let largest = library.search(&LibraryQuery {
+            sort: SortKey::Size,
+            descending: true,
+            page: 1,
+            page_size: 3,
+            ..LibraryQuery::default()
+        });
+        assert_eq!(largest.total, STARTER_SET.len());
+        assert_eq!(largest.entries.len(), 3);
+        // Page 0 is 50x50, 15x15 and the first 10x10; ties keep the order added
+        assert_eq!(largest.entries[0].id, "backtrack_required_10x10");
+
+        let bounded = library.search(&LibraryQuery {
+            min_size: Some(10),
+            max_size: Some(15),
+            ..LibraryQuery::default()
+        });
+        assert_eq!(bounded.total, 3);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_changes_persist() {
+        let dir = temp_library_dir("persist");
+        let mut library = Library::open(&dir).unwrap();
+        let puzzle = library.load("easy_5x5").unwrap();
+        let added = library.add(&puzzle, vec!["copy".to_string()]).unwrap();
+        assert_eq!(added.duplicates[0].id, "easy_5x5");
+        assert_eq!(added.duplicates[0].kind, DuplicateKind::Exact);
+        let added = added.entry;
+        assert_eq!(added.id, format!("puzzle-{}", STARTER_SET.len() + 1));
+        library.set_status(&added.id, SolveStatus::Solved).unwrap();
+        library.remove("no_solution").unwrap();
+
+        let reopened = Library::open(&dir).unwrap();
+        let solved = reopened.search(&LibraryQuery {
+            status: Some(SolveStatus::Solved),
+            tags: vec!["copy".to_string()],
+            ..LibraryQuery::default()
+        });
+        assert_eq!(
+            solved.entries,
+            vec![LibraryEntry {
+                status: SolveStatus::Solved,
+                ..added
+            }]
+        );
+        assert_eq!(
+            reopened.load("no_solution").unwrap_err(),
+            LibraryError::NotFound("no_solution".to_string())
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_rebuild_missing_index() {
+        let dir = temp_library_dir("rebuild");
+        let mut library = Library::open(&dir).unwrap();
+        library.remove("edge_1x1").unwrap();
+        fs::remove_file(dir.join(INDEX_FILE_NAME)).unwrap();
+
+        // Re-indexed from the files on disk, not refilled with the starter set
+        let rebuilt = Library::open(&dir).unwrap();
+        let page = rebuilt.search(&LibraryQuery::default());
+        assert_eq!(page.total, STARTER_SET.len() - 1);
+        assert!(page.entries.iter().all(|e| e.tags.is_empty()));
+        assert!(dir.join(INDEX_FILE_NAME).exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_unreadable_index_is_kept() {
+        let dir = temp_library_dir("unreadable");
+        Library::open(&dir).unwrap();
+        let index_path = dir.join(INDEX_FILE_NAME);
+
+        fs::write(&index_path, "{ not json").unwrap();
+        assert!(matches!(
+            Library::open(&dir),
+            Err(LibraryError::InvalidJson(_))
+        ));
+        assert_eq!(fs::read_to_string(&index_path).unwrap(), "{ not json");
+
+        // An index from a newer app is not rebuilt over either
+        let newer = format!(r#"{{"version": {}, "entries": []}}"#, INDEX_VERSION + 1);
+        fs::write(&index_path, &newer).unwrap();
+        assert!(matches!(
+            Library::open(&dir),
+            Err(LibraryError::UnsupportedVersion { .. })
+        ));
+        assert_eq!(fs::read_to_string(&index_path).unwrap(), newer);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_import_skips_duplicates() {
+        let dir = temp_library_dir("import");
+        let mut library = Library::open(&dir).unwrap();
+
+        // The heart turned on its side, then a broken file
+        let mut turned = library.load("heart_15x15").unwrap();
+        std::mem::swap(&mut turned.row_hints, &mut turned.col_hints);
+        turned.solution = None;
+        let pack = dir.join("pack");
+        fs::create_dir_all(&pack).unwrap();
+        let paths = vec![pack.join("turned.json"), pack.join("broken.json")];
+        file_io::save_puzzle_file(&paths[0], &turned).unwrap();
+        fs::write(&paths[1], "{").unwrap();
+
+        let report = library.import(&paths, &[], true, Locale::En).unwrap();
+        assert!(report.added.is_empty());
+        assert_eq!(
+            report.skipped[0].duplicates,
+            vec![Duplicate {
+                id: "heart_15x15".to_string(),
+                title: Some("ハート型15x15".to_string()),
+                kind: DuplicateKind::Symmetric,
+            }]
+        );
+        assert!(report.failed[0].message.starts_with("Invalid JSON"));
+
+        let report = library.import(&paths[..1], &[], false, Locale::En).unwrap();
+        assert_eq!(report.added.len(), 1);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_fill_fingerprints_of_old_index() {
+        let dir = temp_library_dir("upgrade");
+        Library::open(&dir).unwrap();
+        let index_path = dir.join(INDEX_FILE_NAME);
+        let mut index: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&index_path).unwrap()).unwrap();
+        index["version"] = serde_json::Value::from(1);
+        for entry in index["entries"].as_array_mut().unwrap() {
+            entry.as_object_mut().unwrap().remove("fingerprint");
+        }
+        fs::write(&index_path, index.to_string()).unwrap();
+
+        let library = Library::open(&dir).unwrap();
+        let puzzle = library.load("medium_10x10").unwrap();
+        assert_eq!(library.duplicates_of(&puzzle)[0].kind, DuplicateKind::Exact);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,327 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use crate::puzzle::hint_generator::hints_of;
+use crate::solver::backtrack::backtrack_solve;
+use crate::solver::options::SolverOptions;
+use crate::solver::puzzle::Puzzle;
+use crate::solver::types::{CellState, Grid, SolverSettings, ValidationError};
+
+/// Puzzles whose solutions are kept; the cache is emptied when it fills up.
+const CACHE_CAPACITY: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Solutions {
+    Unique(Grid),
+    Multiple,
+    None,
+}
+
+/// Solver results per puzzle, so that checking each move does not solve again.
+#[derive(Default)]
+pub struct SolutionCache {
+    entries: Mutex<HashMap<Puzzle, Solutions>>,
+}
+
+impl SolutionCache {
+    /// Solve the puzzle unless it is cached; `None` if the deadline passed first.
+    /// Timeouts are not cached, so a later call with more time can still succeed.
+    fn solutions(&self, puzzle: &Puzzle, deadline: Option<Instant>) -> Option<Solutions> {
+        let cached = self
+            .entries
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(puzzle)
+            .cloned();
+        if cached.is_some() {
+            return cached;
+        }
+
+        let options = SolverOptions {
+            deadline,
+            ..SolverOptions::default()
+        };
+        let (mut found, stopped) = backtrack_solve(&puzzle.unknown_grid(), puzzle, &options);
+        // Two solutions settle the question even if the search ran out of time
+        if stopped.is_some() && found.len() < 2 {
+            return None;
+        }
+        let solutions = match found.len() {
+            0 => Solutions::None,
+            1 => Solutions::Unique(found.remove(0)),
+            _ => Solutions::Multiple,
+        };
+
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        if entries.len() >= CACHE_CAPACITY {
+            entries.clear();
+        }
+        entries.insert(puzzle.clone(), solutions.clone());
+        Some(solutions)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CellPosition {
+    pub row: usize,
+    pub col: usize,
+}
+
+/// Feedback on a player's grid that never reveals unmarked cells.
+///
+/// A line is done when its filled cells are final: for a unique puzzle they
+/// match the solution, otherwise they reproduce the line's hints.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum MoveCheck {
+    /// Every wrong mark is reported: filled cells that should be empty and
+    /// crossed-out cells that should be filled.
+    Unique {
+        wrong_cells: Vec<CellPosition>,
+        rows_done: Vec<bool>,
+        cols_done: Vec<bool>,
+        solved: bool,
+    },
+    /// The puzzle has several solutions, so single marks cannot be judged;
+    /// `consistent` tells whether all marks together still fit at least one.
+    Ambiguous {
+        consistent: bool,
+        rows_done: Vec<bool>,
+        cols_done: Vec<bool>,
+        solved: bool,
+    },
+    NoSolution,
+    /// The solver did not finish in time.
+    Timeout,
+}
+
+/// Check a player's grid against the puzzle's solutions.
+/// Assumes the grid has the puzzle's size.
+pub fn check_moves(
+    cache: &SolutionCache,
+    puzzle: &Puzzle,
+    grid: &Grid,
+    deadline: Option<Instant>,
+) -> MoveCheck {
+    match cache.solutions(puzzle, deadline) {
+        None => MoveCheck::Timeout,
+        Some(Solutions::None) => MoveCheck::NoSolution,
+        Some(Solutions::Unique(solution)) => check_unique(&solution, grid),
+        Some(Solutions::Multiple) => {
+            // Marks are constraints: search for any solution that keeps them
+            let options = SolverOptions {
+                max_solutions: 1,
+                deadline,
+                ..SolverOptions::default()
+            };
+            let (found, stopped) = backtrack_solve(grid, puzzle, &options);
+            if found.is_empty() && stopped.is_some() {
+                return MoveCheck::Timeout;
+            }
+            let (rows_done, cols_done) = lines_matching_hints(grid, puzzle);
+            let solved = rows_done.iter().chain(&cols_done).all(|&done| done);
+            MoveCheck::Ambiguous {
+                consistent: !found.is_empty(),
+                rows_done,
+                cols_done,
+                solved,
+            }
+        }
+    }
+}
+
+/// Why a player's grid could not be checked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoveCheckError {
+    InvalidPuzzle(Vec<ValidationError>),
+    /// The grid does not have the puzzle's size.
+    GridMismatch,
+}
+
+/// `check_moves` for hints and a grid as the frontend sends them: the puzzle
+/// is sized by the hints and checked under `settings`, and the grid must fit it.
+pub fn check_player_grid(
+    cache: &SolutionCache,
+    row_hints: Vec<Vec<u32>>,
+    col_hints: Vec<Vec<u32>>,
+    grid: &Grid,
+    settings: &SolverSettings,
+    deadline: Option<Instant>,
+) -> Result<MoveCheck, MoveCheckError> {
+    let (width, height) = (col_hints.len(), row_hints.len());
+    let puzzle = Puzzle::with_settings(width, height, row_hints, col_hints, settings)
+        .map_err(MoveCheckError::InvalidPuzzle)?;
+    if grid.len() != height || grid.iter().any(|row| row.len() != width) {
+        return Err(MoveCheckError::GridMismatch);
+    }
+    Ok(check_moves(cache, &puzzle, grid, deadline))
+}
+
+fn check_unique(solution: &Grid, grid: &Grid) -> MoveCheck {
+    let is_filled = |cell: CellState| cell == CellState::Filled;
+    let mut wrong_cells = Vec::new();
+    for (row, (marks, answer)) in grid.iter().zip(solution).enumerate() {
+        for (col, (&mark, &expected)) in marks.iter().zip(answer).enumerate() {
+            if mark != CellState::Unknown && is_filled(mark) != is_filled(expected) {
+                wrong_cells.push(CellPosition { row, col });
+            }
+        }
+    }
+
+    let height = solution.len();
+    let width = solution.first().map_or(0, |r| r.len());
+    let rows_done: Vec<bool> = (0..height)
+        .map(|r| (0..width).all(|c| is_filled(grid[r][c]) == is_filled(solution[r][c])))
+        .collect();
+    let cols_done: Vec<bool> = (0..width)
+        .map(|c| (0..height).all(|r| is_filled(grid[r][c]) == is_filled(solution[r][c])))
+        .collect();
+    let solved = rows_done.iter().all(|&done| done);
+    MoveCheck::Unique {
+        wrong_cells,
+        rows_done,
+        cols_done,
+        solved,
+    }
+}
+
+/// Whether the filled cells of each row and column reproduce its hints.
+fn lines_matching_hints(grid: &Grid, puzzle: &Puzzle) -> (Vec<bool>, Vec<bool>) {
+    let filled: Vec<Vec<u8>> = grid
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|&c| (c == CellState::Filled) as u8)
+                .collect()
+        })
+        .collect();
+    let (rows, cols) = hints_of(&filled);
+    let matching = |generated: &[Vec<u32>], given: &[Vec<u32>]| {
+        generated.iter().zip(given).map(|(g, h)| g == h).collect()
+    };
+    (
+        matching(&rows, puzzle.row_hints()),
+        matching(&cols, puzzle.col_hints()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use CellState::{Empty as X, Filled as F, Unknown as U};
+
+    // Cross pattern 3x3
+    //   □ ■ □
+    //   ■ ■ ■
+    //   □ ■ □
+    fn cross() -> Puzzle {
+        let hints = vec![vec![1], vec![3], vec![1]];
+        Puzzle::from_hints(hints.clone(), hints).unwrap()
+    }
+
+    // Two diagonals fit: [[1,0],[0,1]] and [[0,1],[1,0]]
+    fn diagonal() -> Puzzle {
+        Puzzle::from_hints(vec![vec![1]; 2], vec![vec![1]; 2]).unwrap()
+    }
+
+    #[test]
+    fn test_unique_reports_wrong_marks() {
+        let cache = SolutionCache::default();
+        let puzzle = cross();
+        let grid = vec![vec![F, F, U], vec![F, X, F], vec![U, U, X]];
+        assert_eq!(
+            check_moves(&cache, &puzzle, &grid, None),
+            MoveCheck::Unique {
+                wrong_cells: vec![
+                    CellPosition { row: 0, col: 0 },
+                    CellPosition { row: 1, col: 1 },
+                ],
+                rows_done: vec![false, false, false],
+                cols_done: vec![false, false, true],
+                solved: false,
+            }
+        );
+
+        let grid = vec![vec![U, F, U], vec![F, F, F], vec![X, F, U]];
+        let MoveCheck::Unique {
+            wrong_cells,
+            solved,
+            ..
+        } = check_moves(&cache, &puzzle, &grid, None)
+        else {
+            panic!("expected a unique puzzle");
+        };
+        assert!(wrong_cells.is_empty());
+        assert!(solved);
+        assert_eq!(cache.entries.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_ambiguous_checks_consistency() {
+        let cache = SolutionCache::default();
+        let puzzle = diagonal();
+
+        let check = check_moves(&cache, &puzzle, &vec![vec![F, U], vec![U, U]], None);
+        assert_eq!(
+            check,
+            MoveCheck::Ambiguous {
+                consistent: true,
+                rows_done: vec![true, false],
+                cols_done: vec![true, false],
+                solved: false,
+            }
+        );
+
+        let check = check_moves(&cache, &puzzle, &vec![vec![F, U], vec![F, U]], None);
+        assert!(matches!(
+            check,
+            MoveCheck::Ambiguous {
+                consistent: false,
+                ..
+            }
+        ));
+
+        let check = check_moves(&cache, &puzzle, &vec![vec![U, F], vec![F, U]], None);
+        assert!(matches!(check, MoveCheck::Ambiguous { solved: true, .. }));
+    }
+
+    #[test]
+    fn test_no_solution() {
+        let cache = SolutionCache::default();
+        let puzzle = Puzzle::from_hints(vec![vec![2], vec![]], vec![vec![], vec![1]]).unwrap();
+        let grid = vec![vec![U; 2]; 2];
+        assert_eq!(
+            check_moves(&cache, &puzzle, &grid, None),
+            MoveCheck::NoSolution
+        );
+    }
+
+    #[test]
+    fn test_check_player_grid() {
+        let cache = SolutionCache::default();
+        let settings = SolverSettings::default();
+        let hints = vec![vec![1], vec![3], vec![1]];
+        let check = |rows: Vec<Vec<u32>>, grid: &Grid| {
+            check_player_grid(&cache, rows, hints.clone(), grid, &settings, None)
+        };
+
+        let grid = vec![vec![U; 3]; 3];
+        assert!(matches!(
+            check(hints.clone(), &grid),
+            Ok(MoveCheck::Unique { .. })
+        ));
+        assert_eq!(
+            check(hints.clone(), &vec![vec![U; 3], vec![U; 2], vec![U; 3]]),
+            Err(MoveCheckError::GridMismatch)
+        );
+        // Every error is kept, not just the first
+        let too_long = vec![vec![4], vec![5], vec![1]];
+        let Err(MoveCheckError::InvalidPuzzle(errors)) = check(too_long, &grid) else {
+            panic!("expected invalid hints");
+        };
+        assert!(errors.len() >= 2, "{:?}", errors);
+    }
+}
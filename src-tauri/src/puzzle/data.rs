@@ -1,6 +1,14 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use super::migration::CURRENT_VERSION;
+use super::solution::{
+    check_against_hints, compare_with_result, decode_solution, encode_solution, SolutionCheck,
+    SolutionError,
+};
+use crate::solver::puzzle::Puzzle;
+use crate::solver::types::{SolveResult, ValidationError};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PuzzleData {
     pub version: String,
@@ -9,9 +17,12 @@ pub struct PuzzleData {
     pub row_hints: Vec<Vec<u32>>,
     pub col_hints: Vec<Vec<u32>>,
     pub metadata: Option<PuzzleMetadata>,
+    /// Intended picture, encoded with `solution::encode_solution`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub solution: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PuzzleMetadata {
     pub title: Option<String>,
@@ -19,3 +30,104 @@ pub struct PuzzleMetadata {
     pub difficulty: Option<String>,
     pub created_at: Option<String>,
 }
+
+impl PuzzleData {
+    /// Decode the stored solution and check it against the hints.
+    pub fn solution_grid(&self) -> Result<Option<Vec<Vec<u8>>>, SolutionError> {
+        let Some(encoded) = &self.solution else {
+            return Ok(None);
+        };
+        let grid = decode_solution(encoded, self.width, self.height)?;
+        check_against_hints(&grid, &self.row_hints, &self.col_hints)?;
+        Ok(Some(grid))
+    }
+
+    /// Compare a solve result with the stored solution.
+    pub fn check_result(&self, result: &SolveResult) -> Result<SolutionCheck, SolutionError> {
+        Ok(match self.solution_grid()? {
+            Some(grid) => compare_with_result(&grid, result),
+            None => SolutionCheck::NoIntendedSolution,
+        })
+    }
+
+    pub fn set_solution(&mut self, grid: &[Vec<u8>]) {
+        self.solution = Some(encode_solution(grid));
+    }
+}
+
+/// Validates the hints against the declared size; metadata and solution are dropped.
+impl TryFrom<&PuzzleData> for Puzzle {
+    type Error = Vec<ValidationError>;
+
+    fn try_from(data: &PuzzleData) -> Result<Self, Self::Error> {
+        Puzzle::new(
+            data.width,
+            data.height,
+            data.row_hints.clone(),
+            data.col_hints.clone(),
+        )
+    }
+}
+
+/// A puzzle file in the current format version, without metadata or solution.
+impl From<&Puzzle> for PuzzleData {
+    fn from(puzzle: &Puzzle) -> Self {
+        PuzzleData {
+            version: CURRENT_VERSION.to_string(),
+            width: puzzle.width(),
+            height: puzzle.height(),
+            row_hints: puzzle.row_hints().to_vec(),
+            col_hints: puzzle.col_hints().to_vec(),
+            metadata: None,
+            solution: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data(width: usize, row_hints: Vec<Vec<u32>>, col_hints: Vec<Vec<u32>>) -> PuzzleData {
+        PuzzleData {
+            version: "1.0".to_string(),
+            width,
+            height: row_hints.len(),
+            row_hints,
+            col_hints,
+            metadata: None,
+            solution: None,
+        }
+    }
+
+    #[test]
+    fn test_puzzle_conversions() {
+        let puzzle = Puzzle::try_from(&data(2, vec![vec![2], vec![0]], vec![vec![1]; 2])).unwrap();
+        assert_eq!(puzzle.row_hints(), &[vec![2], vec![]]);
+
+        let converted = PuzzleData::from(&puzzle);
+        assert_eq!(converted.version, CURRENT_VERSION);
+        assert_eq!((converted.width, converted.height), (2, 2));
+        assert_eq!(Puzzle::try_from(&converted), Ok(puzzle));
+    }
+
+    // Reached from the frontend without the structure check of a loaded file
+    #[test]
+    fn test_solution_grid_rejects_impossible_sizes() {
+        let data: PuzzleData = serde_json::from_str(
+            r#"{"version": "1.1", "width": 0, "height": 100000000000000,
+                "rowHints": [], "colHints": [], "metadata": null, "solution": ""}"#,
+        )
+        .unwrap();
+        assert_eq!(data.solution_grid(), Err(SolutionError::Malformed));
+    }
+
+    #[test]
+    fn test_puzzle_uses_declared_size() {
+        let errors = Puzzle::try_from(&data(3, vec![vec![1]; 2], vec![vec![1]; 2])).unwrap_err();
+        assert!(matches!(
+            errors[..],
+            [ValidationError::HintCountMismatch { expected: 3, .. }]
+        ));
+    }
+}
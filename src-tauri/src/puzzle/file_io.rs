@@ -2,18 +2,228 @@ use std::fs;
 use std::path::Path;
 
 use super::data::PuzzleData;
+use super::migration::{migrate, CURRENT_VERSION};
+use super::solution::SolutionError;
+use crate::solver::types::{Axis, Dimension, SolverSettings, ValidationError, GRID_SIZE_LIMIT};
+use crate::solver::validator::validate_structure;
 
-pub fn load_puzzle_file(path: &Path) -> Result<PuzzleData, String> {
-    let content =
-        fs::read_to_string(path).map_err(|e| format!("ファイルの読み込みに失敗しました: {}", e))?;
-    let puzzle: PuzzleData = serde_json::from_str(&content)
-        .map_err(|e| format!("JSON形式が不正です: {}", e))?;
+/// Failure while reading or writing a puzzle file.
+/// Each variant carries the underlying error's description.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileError {
+    Read(String),
+    InvalidJson(String),
+    /// The file was written by a newer version of the app.
+    UnsupportedVersion { found: String, supported: String },
+    /// The version string is not one this app has ever written.
+    UnknownVersion(String),
+    /// Sizes and hints disagree; `path` locates the offending value, e.g. `colHints[11]`.
+    InvalidStructure { path: String, error: ValidationError },
+    /// The stored solution cannot be decoded or does not match the hints.
+    InvalidSolution(SolutionError),
+    Serialize(String),
+    Write(String),
+}
+
+pub fn load_puzzle_file(path: &Path) -> Result<PuzzleData, FileError> {
+    let content = fs::read_to_string(path).map_err(|e| FileError::Read(e.to_string()))?;
+    parse_puzzle(&content)
+}
+
+/// Parse puzzle JSON of any supported version into the current layout.
+pub fn parse_puzzle(content: &str) -> Result<PuzzleData, FileError> {
+    let value: serde_json::Value =
+        serde_json::from_str(content).map_err(|e| FileError::InvalidJson(e.to_string()))?;
+    puzzle_from_value(value)
+}
+
+/// Same as `parse_puzzle`, for puzzle JSON embedded in another document.
+pub fn puzzle_from_value(value: serde_json::Value) -> Result<PuzzleData, FileError> {
+    let puzzle: PuzzleData = serde_json::from_value(migrate(value)?)
+        .map_err(|e| FileError::InvalidJson(e.to_string()))?;
+    check_puzzle(&puzzle)?;
     Ok(puzzle)
 }
 
-pub fn save_puzzle_file(path: &Path, puzzle: &PuzzleData) -> Result<(), String> {
-    let json = serde_json::to_string_pretty(puzzle)
-        .map_err(|e| format!("シリアライズに失敗しました: {}", e))?;
-    fs::write(path, json).map_err(|e| format!("ファイルの書き込みに失敗しました: {}", e))?;
+/// The checks every puzzle passes on reading, for puzzles about to be
+/// written so that they can be read back.
+pub fn check_puzzle(puzzle: &PuzzleData) -> Result<(), FileError> {
+    check_structure(puzzle)?;
+    puzzle.solution_grid().map_err(FileError::InvalidSolution)?;
+    Ok(())
+}
+
+/// Reject puzzles whose sizes and hints disagree, naming the first offending value.
+/// Sizes are bounded by `GRID_SIZE_LIMIT` rather than the user's setting so that
+/// any puzzle the app could ever solve can still be opened.
+fn check_structure(puzzle: &PuzzleData) -> Result<(), FileError> {
+    let limits = SolverSettings {
+        max_grid_size: GRID_SIZE_LIMIT,
+    };
+    let errors = validate_structure(
+        puzzle.width,
+        puzzle.height,
+        &puzzle.row_hints,
+        &puzzle.col_hints,
+        &limits,
+    );
+    match errors.into_iter().next() {
+        None => Ok(()),
+        Some(error) => Err(FileError::InvalidStructure {
+            path: json_path(&error),
+            error,
+        }),
+    }
+}
+
+fn json_path(error: &ValidationError) -> String {
+    let hints_key = |axis: &Axis| match axis {
+        Axis::Row => "rowHints",
+        Axis::Column => "colHints",
+    };
+    match error {
+        ValidationError::DimensionOutOfRange { dimension, .. } => match dimension {
+            Dimension::Width => "width".to_string(),
+            Dimension::Height => "height".to_string(),
+        },
+        ValidationError::HintCountMismatch { axis, .. } => hints_key(axis).to_string(),
+        ValidationError::HintTooLong { axis, index, .. }
+        | ValidationError::FillCountUnreachable { axis, index, .. }
+        | ValidationError::FillCountExceeded { axis, index, .. }
+        | ValidationError::LineContradiction { axis, index } => {
+            format!("{}[{}]", hints_key(axis), index)
+        }
+        ValidationError::ZeroInMultiHint {
+            axis,
+            index,
+            position,
+        } => format!("{}[{}][{}]", hints_key(axis), index, position),
+        ValidationError::SumMismatch { .. } => String::new(),
+    }
+}
+
+/// Save a puzzle; the file is always written in the current format version.
+pub fn save_puzzle_file(path: &Path, puzzle: &PuzzleData) -> Result<(), FileError> {
+    check_puzzle(puzzle)?;
+    let puzzle = PuzzleData {
+        version: CURRENT_VERSION.to_string(),
+        ..puzzle.clone()
+    };
+    let json =
+        serde_json::to_string_pretty(&puzzle).map_err(|e| FileError::Serialize(e.to_string()))?;
+    fs::write(path, json).map_err(|e| FileError::Write(e.to_string()))?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!(
+            "illust-logic-file-io-{}-{}.json",
+            std::process::id(),
+            name
+        ))
+    }
+
+    fn sample_puzzle() -> PuzzleData {
+        PuzzleData {
+            version: "1.0".to_string(),
+            width: 3,
+            height: 2,
+            row_hints: vec![vec![2], vec![1]],
+            col_hints: vec![vec![1], vec![2], vec![]],
+            metadata: None,
+            solution: None,
+        }
+    }
+
+    #[test]
+    fn test_solution_roundtrip() {
+        let path = temp_path("solution");
+        let mut puzzle = sample_puzzle();
+        puzzle.set_solution(&[vec![1, 1, 0], vec![0, 1, 0]]);
+        save_puzzle_file(&path, &puzzle).unwrap();
+
+        let loaded = load_puzzle_file(&path).unwrap();
+        assert_eq!(loaded.version, CURRENT_VERSION);
+        assert_eq!(loaded.solution, puzzle.solution);
+        assert_eq!(
+            loaded.solution_grid(),
+            Ok(Some(vec![vec![1, 1, 0], vec![0, 1, 0]]))
+        );
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_reject_mismatched_solution() {
+        let path = temp_path("mismatch");
+        let mut puzzle = sample_puzzle();
+        puzzle.set_solution(&[vec![0, 1, 1], vec![0, 1, 0]]);
+        assert_eq!(
+            save_puzzle_file(&path, &puzzle),
+            Err(FileError::InvalidSolution(SolutionError::HintMismatch))
+        );
+
+        puzzle.version = CURRENT_VERSION.to_string();
+        let json = serde_json::to_string(&puzzle).unwrap();
+        fs::write(&path, json).unwrap();
+        assert_eq!(
+            load_puzzle_file(&path).unwrap_err(),
+            FileError::InvalidSolution(SolutionError::HintMismatch)
+        );
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_reject_inconsistent_sizes() {
+        let mut puzzle = sample_puzzle();
+        puzzle.col_hints.push(vec![]);
+        let json = serde_json::to_string(&puzzle).unwrap();
+        assert_eq!(
+            parse_puzzle(&json).unwrap_err(),
+            FileError::InvalidStructure {
+                path: "colHints".to_string(),
+                error: ValidationError::HintCountMismatch {
+                    axis: Axis::Column,
+                    count: 4,
+                    expected: 3,
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn test_reject_hint_paths() {
+        let mut puzzle = sample_puzzle();
+        puzzle.col_hints[2] = vec![1, 1];
+        let json = serde_json::to_string(&puzzle).unwrap();
+        let FileError::InvalidStructure { path, .. } = parse_puzzle(&json).unwrap_err() else {
+            panic!("expected a structure error");
+        };
+        assert_eq!(path, "colHints[2]");
+
+        puzzle.col_hints[2] = vec![];
+        puzzle.row_hints[1] = vec![1, 0];
+        let json = serde_json::to_string(&puzzle).unwrap();
+        let FileError::InvalidStructure { path, .. } = parse_puzzle(&json).unwrap_err() else {
+            panic!("expected a structure error");
+        };
+        assert_eq!(path, "rowHints[1][1]");
+    }
+
+    #[test]
+    fn test_reject_absurd_sizes() {
+        let json = r#"{"version": "1.1", "width": 4000000000, "height": 1,
+            "rowHints": [[]], "colHints": [], "metadata": null}"#;
+        let FileError::InvalidStructure { path, .. } = parse_puzzle(json).unwrap_err() else {
+            panic!("expected a structure error");
+        };
+        assert_eq!(path, "width");
+
+        let json = r#"{"version": "1.1", "width": -1, "height": 1,
+            "rowHints": [[]], "colHints": [], "metadata": null}"#;
+        assert!(matches!(parse_puzzle(json), Err(FileError::InvalidJson(_))));
+    }
+}
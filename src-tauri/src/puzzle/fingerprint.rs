@@ -0,0 +1,125 @@
+use serde::{Deserialize, Serialize};
+
+use super::transform::Transform;
+use crate::solver::puzzle::normalize_hints;
+
+type Hints = Vec<Vec<u32>>;
+
+/// Identifies a puzzle by its hints alone, whatever its title or metadata.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Fingerprint {
+    /// Equal for the same hints in the same orientation.
+    pub exact: String,
+    /// Equal for the same hints up to flips, rotations and transposition.
+    pub symmetric: String,
+}
+
+impl Fingerprint {
+    pub fn new(row_hints: &[Vec<u32>], col_hints: &[Vec<u32>]) -> Self {
+        Fingerprint {
+            exact: exact_hash(row_hints, col_hints),
+            symmetric: symmetric_hash(row_hints, col_hints),
+        }
+    }
+}
+
+/// A 64-bit FNV-1a hash of the hints, in hex.
+/// `[0]` and `[]` hash the same, as they describe the same line.
+pub fn exact_hash(row_hints: &[Vec<u32>], col_hints: &[Vec<u32>]) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut feed = |value: u32| {
+        for byte in value.to_le_bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    };
+    for lines in [row_hints, col_hints] {
+        feed(lines.len() as u32);
+        for hints in lines {
+            let hints = normalize_hints(hints);
+            feed(hints.len() as u32);
+            hints.iter().for_each(|&h| feed(h));
+        }
+    }
+    format!("{:016x}", hash)
+}
+
+/// The exact hash of the smallest of the puzzle's eight orientations,
+/// so every orientation of a picture gets the same value.
+pub fn symmetric_hash(row_hints: &[Vec<u32>], col_hints: &[Vec<u32>]) -> String {
+    let normalize =
+        |lines: &[Vec<u32>]| -> Hints { lines.iter().map(|h| normalize_hints(h)).collect() };
+    let (rows, cols) = (normalize(row_hints), normalize(col_hints));
+    let smallest = Transform::ALL
+        .iter()
+        .map(|t| t.apply_hints(&rows, &cols))
+        .fold((rows.clone(), cols.clone()), |a, b| a.min(b));
+    exact_hash(&smallest.0, &smallest.1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzle::hint_generator::generate_hints;
+
+    // An asymmetric picture
+    //   ■ ■ □ □
+    //   ■ □ □ ■
+    //   ■ □ □ □
+    fn picture() -> Vec<Vec<u8>> {
+        vec![vec![1, 1, 0, 0], vec![1, 0, 0, 1], vec![1, 0, 0, 0]]
+    }
+
+    fn rotate_clockwise(grid: &[Vec<u8>]) -> Vec<Vec<u8>> {
+        let height = grid.len();
+        (0..grid[0].len())
+            .map(|c| (0..height).rev().map(|r| grid[r][c]).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_exact_hash() {
+        let rows = vec![vec![1], vec![0]];
+        let cols = vec![vec![1], vec![]];
+        assert_eq!(
+            exact_hash(&rows, &cols),
+            exact_hash(&[vec![1], vec![]], &cols)
+        );
+        // Swapping rows and columns is a different puzzle
+        assert_ne!(
+            exact_hash(&[vec![1, 1]], &[vec![1], vec![], vec![1]]),
+            exact_hash(&[vec![1], vec![], vec![1]], &[vec![1, 1]])
+        );
+        assert_eq!(exact_hash(&rows, &cols).len(), 16);
+    }
+
+    #[test]
+    fn test_symmetric_hash_ignores_orientation() {
+        let (rows, cols) = generate_hints(&picture()).unwrap();
+        let original = Fingerprint::new(&rows, &cols);
+
+        let mut grid = picture();
+        let mut exact = vec![original.exact.clone()];
+        for _ in 0..4 {
+            grid = rotate_clockwise(&grid);
+            let mirrored: Vec<Vec<u8>> = grid
+                .iter()
+                .map(|row| row.iter().rev().copied().collect())
+                .collect();
+            for variant in [&grid, &mirrored] {
+                let (rows, cols) = generate_hints(variant).unwrap();
+                let fingerprint = Fingerprint::new(&rows, &cols);
+                assert_eq!(fingerprint.symmetric, original.symmetric);
+                exact.push(fingerprint.exact);
+            }
+        }
+        // The four rotations come back to the start; the other six are distinct
+        exact.sort();
+        exact.dedup();
+        assert_eq!(exact.len(), 8);
+
+        let (rows, cols) = generate_hints(&[vec![1, 1, 1, 0], vec![1, 0, 0, 1], vec![1, 0, 0, 0]]).unwrap();
+        assert_ne!(Fingerprint::new(&rows, &cols).symmetric, original.symmetric);
+    }
+}
@@ -1,13 +1,43 @@
+use crate::solver::types::{SolverSettings, ValidationError, GRID_SIZE_LIMIT};
+use crate::solver::validator::validate_dimensions;
+
+type Hints = Vec<Vec<u32>>;
+
 /// Generate row and column hints from a grid of 0s and 1s.
-pub fn generate_hints(grid: &[Vec<u8>]) -> (Vec<Vec<u32>>, Vec<Vec<u32>>) {
+/// Rows shorter than the longest are read as ending in empty cells.
+/// A size no puzzle can have is refused before any hint is built.
+pub fn generate_hints(grid: &[Vec<u8>]) -> Result<(Hints, Hints), Vec<ValidationError>> {
+    picture_size(grid)?;
+    Ok(hints_of(grid))
+}
+
+/// Width and height of a picture, the width being its longest row,
+/// checked against `GRID_SIZE_LIMIT`.
+pub fn picture_size(grid: &[Vec<u8>]) -> Result<(usize, usize), Vec<ValidationError>> {
+    let width = grid.iter().map(|row| row.len()).max().unwrap_or(0);
+    let settings = SolverSettings {
+        max_grid_size: GRID_SIZE_LIMIT,
+    };
+    let errors = validate_dimensions(width, grid.len(), &settings);
+    if errors.is_empty() {
+        Ok((width, grid.len()))
+    } else {
+        Err(errors)
+    }
+}
+
+/// `generate_hints` for a grid whose size the caller has already bounded.
+pub(crate) fn hints_of(grid: &[Vec<u8>]) -> (Hints, Hints) {
     let height = grid.len();
-    let width = if height > 0 { grid[0].len() } else { 0 };
+    let width = grid.iter().map(|row| row.len()).max().unwrap_or(0);
 
-    let row_hints: Vec<Vec<u32>> = grid.iter().map(|row| line_hint(row)).collect();
+    let row_hints: Hints = grid.iter().map(|row| line_hint(row)).collect();
 
-    let col_hints: Vec<Vec<u32>> = (0..width)
+    let col_hints: Hints = (0..width)
         .map(|c| {
-            let col: Vec<u8> = (0..height).map(|r| grid[r][c]).collect();
+            let col: Vec<u8> = (0..height)
+                .map(|r| grid[r].get(c).copied().unwrap_or(0))
+                .collect();
             line_hint(&col)
         })
         .collect();
@@ -61,8 +91,30 @@ mod tests {
             vec![1, 1, 1],
             vec![0, 1, 0],
         ];
-        let (row_hints, col_hints) = generate_hints(&grid);
+        let (row_hints, col_hints) = generate_hints(&grid).unwrap();
         assert_eq!(row_hints, vec![vec![1, 1], vec![3], vec![1]]);
         assert_eq!(col_hints, vec![vec![2], vec![2], vec![2]]);
     }
+
+    #[test]
+    fn test_generate_hints_ragged_grid() {
+        let grid = vec![vec![1, 1, 1], vec![1], vec![]];
+        let (row_hints, col_hints) = generate_hints(&grid).unwrap();
+        assert_eq!(row_hints, vec![vec![3], vec![1], vec![]]);
+        assert_eq!(col_hints, vec![vec![2], vec![1], vec![1]]);
+    }
+
+    #[test]
+    fn test_generate_hints_refuses_impossible_sizes() {
+        // One long row makes every other row as wide as it
+        let grid = vec![vec![1; GRID_SIZE_LIMIT + 1], vec![]];
+        let errors = generate_hints(&grid).unwrap_err();
+        assert!(matches!(
+            errors.as_slice(),
+            [ValidationError::DimensionOutOfRange { value, .. }] if *value == GRID_SIZE_LIMIT + 1
+        ));
+        assert!(generate_hints(&vec![vec![0]; GRID_SIZE_LIMIT + 1]).is_err());
+        assert!(generate_hints(&[]).is_err());
+        assert_eq!(picture_size(&[vec![1, 1], vec![1]]), Ok((2, 2)));
+    }
 }
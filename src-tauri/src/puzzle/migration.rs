@@ -0,0 +1,169 @@
+use serde_json::{Map, Value};
+
+use super::file_io::FileError;
+use super::solution::{check_against_hints, encode_solution};
+
+/// Format version written by this build.
+pub const CURRENT_VERSION: &str = "1.1";
+
+/// Every released layout, oldest first.
+///
+/// - 1.0: hints and metadata only.
+/// - 1.1: adds the optional top-level `solution`.
+const VERSIONS: [&str; 2] = ["1.0", CURRENT_VERSION];
+
+/// `MIGRATIONS[i]` upgrades a `VERSIONS[i]` object to `VERSIONS[i + 1]`.
+const MIGRATIONS: [fn(&mut Map<String, Value>); 1] = [migrate_1_0_to_1_1];
+
+/// Bring a parsed puzzle file up to `CURRENT_VERSION`.
+/// Files from a newer version are rejected rather than guessed at.
+pub fn migrate(value: Value) -> Result<Value, FileError> {
+    let Value::Object(mut puzzle) = value else {
+        return Err(FileError::InvalidJson("expected a JSON object".to_string()));
+    };
+    let version = match puzzle.get("version") {
+        Some(Value::String(v)) => v.clone(),
+        _ => return Err(FileError::InvalidJson("missing version".to_string())),
+    };
+
+    let Some(index) = VERSIONS.iter().position(|v| *v == version) else {
+        return Err(match parse_version(&version) {
+            Some(found) if Some(found) > parse_version(CURRENT_VERSION) => {
+                FileError::UnsupportedVersion {
+                    found: version,
+                    supported: CURRENT_VERSION.to_string(),
+                }
+            }
+            _ => FileError::UnknownVersion(version),
+        });
+    };
+    for step in &MIGRATIONS[index..] {
+        step(&mut puzzle);
+    }
+    Ok(Value::Object(puzzle))
+}
+
+fn parse_version(version: &str) -> Option<(u32, u32)> {
+    let (major, minor) = version.split_once('.')?;
+    Some((major.parse().ok()?, minor.parse().ok()?))
+}
+
+/// Some 1.0 files carry the picture as a 0/1 array in `metadata.solution`.
+/// It becomes the stored solution when it really produces the hints;
+/// otherwise it is dropped, as 1.0 readers ignored it too.
+fn migrate_1_0_to_1_1(puzzle: &mut Map<String, Value>) {
+    let legacy = puzzle
+        .get_mut("metadata")
+        .and_then(Value::as_object_mut)
+        .and_then(|metadata| metadata.remove("solution"));
+
+    if !puzzle.contains_key("solution") {
+        if let Some(grid) = legacy.and_then(|v| legacy_solution(puzzle, v)) {
+            puzzle.insert(
+                "solution".to_string(),
+                Value::String(encode_solution(&grid)),
+            );
+        }
+    }
+    puzzle.insert(
+        "version".to_string(),
+        Value::String(VERSIONS[1].to_string()),
+    );
+}
+
+fn legacy_solution(puzzle: &Map<String, Value>, value: Value) -> Option<Vec<Vec<u8>>> {
+    let grid: Vec<Vec<u8>> = serde_json::from_value(value).ok()?;
+    let width = puzzle.get("width")?.as_u64()? as usize;
+    let height = puzzle.get("height")?.as_u64()? as usize;
+    let rectangular = grid.len() == height && grid.iter().all(|row| row.len() == width);
+    if !rectangular || grid.iter().flatten().any(|&c| c > 1) {
+        return None;
+    }
+    let row_hints: Vec<Vec<u32>> = serde_json::from_value(puzzle.get("rowHints")?.clone()).ok()?;
+    let col_hints: Vec<Vec<u32>> = serde_json::from_value(puzzle.get("colHints")?.clone()).ok()?;
+    check_against_hints(&grid, &row_hints, &col_hints).ok()?;
+    Some(grid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzle::file_io::parse_puzzle;
+
+    fn golden(name: &str) -> Value {
+        let content = match name {
+            "v1_0" => include_str!("testdata/v1_0.json"),
+            "v1_0_mismatched_solution" => include_str!("testdata/v1_0_mismatched_solution.json"),
+            "v1_1" => include_str!("testdata/v1_1.json"),
+            _ => unreachable!(),
+        };
+        serde_json::from_str(content).unwrap()
+    }
+
+    #[test]
+    fn test_migrate_1_0_to_1_1() {
+        // The legacy metadata.solution becomes the stored solution
+        assert_eq!(migrate(golden("v1_0")).unwrap(), golden("v1_1"));
+    }
+
+    #[test]
+    fn test_migrate_1_0_drops_mismatched_solution() {
+        let migrated = migrate(golden("v1_0_mismatched_solution")).unwrap();
+        assert_eq!(migrated["version"], "1.1");
+        assert!(migrated.get("solution").is_none());
+        assert!(migrated["metadata"].get("solution").is_none());
+        assert_eq!(migrated["metadata"]["title"], "十字");
+    }
+
+    #[test]
+    fn test_current_version_unchanged() {
+        assert_eq!(migrate(golden("v1_1")).unwrap(), golden("v1_1"));
+    }
+
+    #[test]
+    fn test_reject_newer_version() {
+        let mut value = golden("v1_1");
+        value["version"] = Value::from("2.0");
+        assert_eq!(
+            migrate(value),
+            Err(FileError::UnsupportedVersion {
+                found: "2.0".to_string(),
+                supported: CURRENT_VERSION.to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_reject_unknown_version() {
+        let mut value = golden("v1_1");
+        value["version"] = Value::from("0.9");
+        assert_eq!(
+            migrate(value),
+            Err(FileError::UnknownVersion("0.9".to_string()))
+        );
+
+        let mut value = golden("v1_1");
+        value.as_object_mut().unwrap().remove("version");
+        assert!(matches!(migrate(value), Err(FileError::InvalidJson(_))));
+    }
+
+    #[test]
+    fn test_bundled_samples_load() {
+        let samples = [
+            include_str!("../../../docs/samples/backtrack_required_10x10.json"),
+            include_str!("../../../docs/samples/easy_5x5.json"),
+            include_str!("../../../docs/samples/edge_1x1.json"),
+            include_str!("../../../docs/samples/edge_all_empty_5x5.json"),
+            include_str!("../../../docs/samples/edge_all_filled_5x5.json"),
+            include_str!("../../../docs/samples/edge_large_50x50.json"),
+            include_str!("../../../docs/samples/heart_15x15.json"),
+            include_str!("../../../docs/samples/medium_10x10.json"),
+            include_str!("../../../docs/samples/multiple_solutions.json"),
+            include_str!("../../../docs/samples/no_solution.json"),
+        ];
+        for content in samples {
+            let puzzle = parse_puzzle(content).unwrap();
+            assert_eq!(puzzle.version, CURRENT_VERSION);
+        }
+    }
+}
@@ -1,3 +1,7 @@
 pub mod data;
 pub mod file_io;
+pub mod fingerprint;
 pub mod hint_generator;
+pub mod migration;
+pub mod solution;
+pub mod transform;
@@ -0,0 +1,236 @@
+use serde::{Deserialize, Serialize};
+
+use super::hint_generator::hints_of;
+use crate::solver::puzzle::normalize_hints;
+use crate::solver::types::{SolveResult, GRID_SIZE_LIMIT};
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Why a stored solution could not be used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolutionError {
+    /// Not valid base64, not the number of bits the grid size needs, or for
+    /// a size no puzzle can have.
+    Malformed,
+    /// The picture does not produce the puzzle's hints.
+    HintMismatch,
+}
+
+/// How the solver's answer compares with the solution stored in the puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum SolutionCheck {
+    /// The puzzle has no stored solution.
+    NoIntendedSolution,
+    /// The solver found exactly the stored picture.
+    Matches,
+    /// The solver's answer is not the stored picture, or not only that one.
+    Differs,
+    /// The solver did not finish or could not run, so nothing can be said.
+    Undetermined,
+}
+
+/// Encode a 0/1 grid compactly: cells are packed row-major, eight per byte with
+/// the first cell in the most significant bit, and the bytes are base64-encoded.
+pub fn encode_solution(grid: &[Vec<u8>]) -> String {
+    let mut bytes = Vec::new();
+    for (i, &cell) in grid.iter().flatten().enumerate() {
+        if i % 8 == 0 {
+            bytes.push(0u8);
+        }
+        if cell != 0 {
+            *bytes.last_mut().unwrap() |= 0x80 >> (i % 8);
+        }
+    }
+    encode_base64(&bytes)
+}
+
+/// Decode a solution written by `encode_solution` for a grid of the given size.
+/// The size is checked first: an empty string fits `0 × n` cells for any `n`,
+/// and building that many rows would exhaust memory.
+pub fn decode_solution(
+    encoded: &str,
+    width: usize,
+    height: usize,
+) -> Result<Vec<Vec<u8>>, SolutionError> {
+    let in_range = |size: usize| (1..=GRID_SIZE_LIMIT).contains(&size);
+    if !in_range(width) || !in_range(height) {
+        return Err(SolutionError::Malformed);
+    }
+    let bytes = decode_base64(encoded).ok_or(SolutionError::Malformed)?;
+    let cells = width.checked_mul(height).ok_or(SolutionError::Malformed)?;
+    if bytes.len() != cells.div_ceil(8) {
+        return Err(SolutionError::Malformed);
+    }
+    let bit = |i: usize| (bytes[i / 8] >> (7 - i % 8)) & 1;
+    // Padding bits after the last cell must be zero
+    if (cells..bytes.len() * 8).any(|i| bit(i) != 0) {
+        return Err(SolutionError::Malformed);
+    }
+    Ok((0..height)
+        .map(|r| (0..width).map(|c| bit(r * width + c)).collect())
+        .collect())
+}
+
+/// Check that a picture produces exactly the given hints.
+pub fn check_against_hints(
+    grid: &[Vec<u8>],
+    row_hints: &[Vec<u32>],
+    col_hints: &[Vec<u32>],
+) -> Result<(), SolutionError> {
+    // The hints bound the size, so a picture larger than them is not read
+    if grid.len() != row_hints.len() || grid.iter().any(|row| row.len() > col_hints.len()) {
+        return Err(SolutionError::HintMismatch);
+    }
+    let (grid_rows, grid_cols) = hints_of(grid);
+    let same = |generated: &[Vec<u32>], given: &[Vec<u32>]| {
+        generated.len() == given.len()
+            && generated
+                .iter()
+                .zip(given)
+                .all(|(g, h)| *g == normalize_hints(h))
+    };
+    if same(&grid_rows, row_hints) && same(&grid_cols, col_hints) {
+        Ok(())
+    } else {
+        Err(SolutionError::HintMismatch)
+    }
+}
+
+/// Compare the stored picture with what the solver found.
+pub fn compare_with_result(intended: &[Vec<u8>], result: &SolveResult) -> SolutionCheck {
+    match result {
+        SolveResult::UniqueSolution { grid, .. } if grid.as_slice() == intended => {
+            SolutionCheck::Matches
+        }
+        SolveResult::UniqueSolution { .. }
+        | SolveResult::MultipleSolutions { .. }
+        | SolveResult::NoSolution { .. } => SolutionCheck::Differs,
+        SolveResult::Timeout { .. }
+        | SolveResult::BudgetExhausted { .. }
+        | SolveResult::InvalidInput { .. } => SolutionCheck::Undetermined,
+    }
+}
+
+fn encode_base64(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = (chunk[0] as u32) << 16
+            | (*chunk.get(1).unwrap_or(&0) as u32) << 8
+            | *chunk.get(2).unwrap_or(&0) as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64_ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let text = text.as_bytes();
+    if text.len() % 4 != 0 {
+        return None;
+    }
+    let mut out = Vec::with_capacity(text.len() / 4 * 3);
+    for (index, chunk) in text.chunks(4).enumerate() {
+        let is_last = index == text.len() / 4 - 1;
+        let padding = chunk.iter().rev().take_while(|&&b| b == b'=').count();
+        if padding > 2 || (padding > 0 && !is_last) {
+            return None;
+        }
+        let mut n = 0u32;
+        for &b in &chunk[..4 - padding] {
+            let value = BASE64_ALPHABET.iter().position(|&a| a == b)? as u32;
+            n = n << 6 | value;
+        }
+        n <<= 6 * padding as u32;
+        let decoded = [(n >> 16) as u8, (n >> 8) as u8, n as u8];
+        out.extend_from_slice(&decoded[..3 - padding]);
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::types::SolveStats;
+
+    #[test]
+    fn test_base64_roundtrip() {
+        for len in 0..10 {
+            let bytes: Vec<u8> = (0..len).map(|i| (i * 37 + 5) as u8).collect();
+            assert_eq!(decode_base64(&encode_base64(&bytes)), Some(bytes));
+        }
+        assert_eq!(encode_base64(b"Man"), "TWFu");
+        assert_eq!(encode_base64(b"Ma"), "TWE=");
+        assert_eq!(decode_base64("TW=u"), None);
+        assert_eq!(decode_base64("TWE"), None);
+    }
+
+    #[test]
+    fn test_solution_roundtrip() {
+        let grid = vec![vec![1, 0, 1], vec![1, 1, 1], vec![0, 1, 0]];
+        let encoded = encode_solution(&grid);
+        // 101 111 010 + 7 padding bits = 0b10111101, 0b00000000
+        assert_eq!(encoded, "vQA=");
+        assert_eq!(decode_solution(&encoded, 3, 3), Ok(grid));
+    }
+
+    #[test]
+    fn test_decode_wrong_size() {
+        let encoded = encode_solution(&vec![vec![1; 4]; 4]);
+        assert_eq!(decode_solution(&encoded, 5, 5), Err(SolutionError::Malformed));
+        // Same byte count, but a set padding bit
+        assert_eq!(decode_solution(&encoded, 3, 4), Err(SolutionError::Malformed));
+    }
+
+    #[test]
+    fn test_decode_rejects_impossible_sizes() {
+        // An empty string would otherwise stand for any number of empty rows
+        assert_eq!(
+            decode_solution("", 0, 100_000_000_000_000),
+            Err(SolutionError::Malformed)
+        );
+        assert_eq!(
+            decode_solution("", 100_000_000_000_000, 0),
+            Err(SolutionError::Malformed)
+        );
+        let too_wide = encode_solution(&[vec![0; GRID_SIZE_LIMIT + 1]]);
+        assert_eq!(
+            decode_solution(&too_wide, GRID_SIZE_LIMIT + 1, 1),
+            Err(SolutionError::Malformed)
+        );
+    }
+
+    #[test]
+    fn test_check_against_hints() {
+        let grid = vec![vec![1, 0], vec![0, 0]];
+        assert_eq!(
+            check_against_hints(&grid, &[vec![1], vec![0]], &[vec![1], vec![]]),
+            Ok(())
+        );
+        assert_eq!(
+            check_against_hints(&grid, &[vec![1], vec![1]], &[vec![1], vec![1]]),
+            Err(SolutionError::HintMismatch)
+        );
+    }
+
+    #[test]
+    fn test_compare_with_result() {
+        let intended = vec![vec![1, 0], vec![0, 1]];
+        let unique = SolveResult::UniqueSolution {
+            grid: intended.clone(),
+            stats: SolveStats::default(),
+        };
+        assert_eq!(compare_with_result(&intended, &unique), SolutionCheck::Matches);
+        let multiple = SolveResult::MultipleSolutions {
+            grids: vec![intended.clone(), vec![vec![0, 1], vec![1, 0]]],
+            stats: SolveStats::default(),
+        };
+        assert_eq!(compare_with_result(&intended, &multiple), SolutionCheck::Differs);
+    }
+}
@@ -0,0 +1,286 @@
+use serde::{Deserialize, Serialize};
+
+use super::data::PuzzleData;
+use super::file_io::{check_puzzle, FileError};
+use crate::solver::types::SolveResult;
+
+type Hints = Vec<Vec<u32>>;
+
+/// A flip, rotation or transposition of the picture.
+/// Rotations are clockwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Transform {
+    /// Mirror across the main diagonal: rows become columns.
+    Transpose,
+    /// Mirror across the other diagonal.
+    AntiTranspose,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    /// Mirror left to right.
+    FlipHorizontal,
+    /// Mirror top to bottom.
+    FlipVertical,
+}
+
+/// The moves every transform is built from, applied in order.
+#[derive(Debug, Clone, Copy)]
+enum Step {
+    Transpose,
+    FlipHorizontal,
+    FlipVertical,
+}
+
+impl Transform {
+    /// Every transform that can change a picture; with the identity they form
+    /// all eight orientations.
+    pub const ALL: [Transform; 7] = [
+        Transform::Transpose,
+        Transform::AntiTranspose,
+        Transform::Rotate90,
+        Transform::Rotate180,
+        Transform::Rotate270,
+        Transform::FlipHorizontal,
+        Transform::FlipVertical,
+    ];
+
+    fn steps(self) -> &'static [Step] {
+        match self {
+            Transform::Transpose => &[Step::Transpose],
+            Transform::AntiTranspose => {
+                &[Step::Transpose, Step::FlipHorizontal, Step::FlipVertical]
+            }
+            Transform::Rotate90 => &[Step::Transpose, Step::FlipHorizontal],
+            Transform::Rotate180 => &[Step::FlipHorizontal, Step::FlipVertical],
+            Transform::Rotate270 => &[Step::Transpose, Step::FlipVertical],
+            Transform::FlipHorizontal => &[Step::FlipHorizontal],
+            Transform::FlipVertical => &[Step::FlipVertical],
+        }
+    }
+
+    /// Whether width and height trade places.
+    pub fn swaps_axes(self) -> bool {
+        matches!(
+            self,
+            Transform::Transpose
+                | Transform::AntiTranspose
+                | Transform::Rotate90
+                | Transform::Rotate270
+        )
+    }
+
+    /// Rearrange the hints; the result describes the transformed picture.
+    pub fn apply_hints(self, row_hints: &[Vec<u32>], col_hints: &[Vec<u32>]) -> (Hints, Hints) {
+        let mut rows = row_hints.to_vec();
+        let mut cols = col_hints.to_vec();
+        for step in self.steps() {
+            match step {
+                Step::Transpose => std::mem::swap(&mut rows, &mut cols),
+                // A mirrored row reads backwards, and the columns swap ends
+                Step::FlipHorizontal => {
+                    rows.iter_mut().for_each(|h| h.reverse());
+                    cols.reverse();
+                }
+                Step::FlipVertical => {
+                    rows.reverse();
+                    cols.iter_mut().for_each(|h| h.reverse());
+                }
+            }
+        }
+        (rows, cols)
+    }
+
+    /// Transform a grid of any cell type, such as a 0/1 picture or a solver grid.
+    /// A ragged grid keeps every cell: transposing it gathers each column from
+    /// the rows long enough to have one.
+    pub fn apply_grid<T: Copy>(self, grid: &[Vec<T>]) -> Vec<Vec<T>> {
+        let mut grid = grid.to_vec();
+        for step in self.steps() {
+            match step {
+                Step::Transpose => {
+                    let width = grid.iter().map(|r| r.len()).max().unwrap_or(0);
+                    grid = (0..width)
+                        .map(|c| grid.iter().filter_map(|row| row.get(c).copied()).collect())
+                        .collect();
+                }
+                Step::FlipHorizontal => grid.iter_mut().for_each(|row| row.reverse()),
+                Step::FlipVertical => grid.reverse(),
+            }
+        }
+        grid
+    }
+
+    /// Transform every picture in a solver result.
+    pub fn apply_result(self, result: &SolveResult) -> SolveResult {
+        match result {
+            SolveResult::UniqueSolution { grid, stats } => SolveResult::UniqueSolution {
+                grid: self.apply_grid(grid),
+                stats: *stats,
+            },
+            SolveResult::MultipleSolutions { grids, stats } => SolveResult::MultipleSolutions {
+                grids: grids.iter().map(|g| self.apply_grid(g)).collect(),
+                stats: *stats,
+            },
+            other => other.clone(),
+        }
+    }
+
+    /// Transform a puzzle's hints, size and stored solution; metadata is kept.
+    /// Fails if the puzzle would not pass the checks of a loaded file.
+    pub fn apply_puzzle(self, puzzle: &PuzzleData) -> Result<PuzzleData, FileError> {
+        check_puzzle(puzzle)?;
+        let (row_hints, col_hints) = self.apply_hints(&puzzle.row_hints, &puzzle.col_hints);
+        let (width, height) = if self.swaps_axes() {
+            (puzzle.height, puzzle.width)
+        } else {
+            (puzzle.width, puzzle.height)
+        };
+        let mut transformed = PuzzleData {
+            width,
+            height,
+            row_hints,
+            col_hints,
+            solution: None,
+            ..puzzle.clone()
+        };
+        if let Some(grid) = puzzle.solution_grid().map_err(FileError::InvalidSolution)? {
+            transformed.set_solution(&self.apply_grid(&grid));
+        }
+        Ok(transformed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzle::hint_generator::generate_hints;
+    use crate::puzzle::solution::SolutionError;
+    use crate::solver::backtrack::backtrack_solve;
+    use crate::solver::options::SolverOptions;
+    use crate::solver::puzzle::Puzzle;
+
+    // An asymmetric picture
+    //   ■ ■ □ □
+    //   ■ □ □ ■
+    //   ■ □ □ □
+    fn picture() -> Vec<Vec<u8>> {
+        vec![vec![1, 1, 0, 0], vec![1, 0, 0, 1], vec![1, 0, 0, 0]]
+    }
+
+    fn inverse(transform: Transform) -> Transform {
+        match transform {
+            Transform::Rotate90 => Transform::Rotate270,
+            Transform::Rotate270 => Transform::Rotate90,
+            other => other,
+        }
+    }
+
+    #[test]
+    fn test_transform_grid() {
+        let grid = picture();
+        assert_eq!(
+            Transform::Rotate90.apply_grid(&grid),
+            vec![vec![1, 1, 1], vec![0, 0, 1], vec![0, 0, 0], vec![0, 1, 0]]
+        );
+        assert_eq!(
+            Transform::AntiTranspose.apply_grid(&grid),
+            vec![vec![0, 1, 0], vec![0, 0, 0], vec![0, 0, 1], vec![1, 1, 1]]
+        );
+        let mut rotated = grid.clone();
+        for _ in 0..4 {
+            rotated = Transform::Rotate90.apply_grid(&rotated);
+        }
+        assert_eq!(rotated, grid);
+
+        for transform in Transform::ALL {
+            let transformed = transform.apply_grid(&grid);
+            assert_eq!(inverse(transform).apply_grid(&transformed), grid);
+            // Rearranged hints match hints generated from the new picture
+            let (rows, cols) = generate_hints(&grid).unwrap();
+            assert_eq!(
+                transform.apply_hints(&rows, &cols),
+                generate_hints(&transformed).unwrap(),
+                "{:?}",
+                transform
+            );
+        }
+    }
+
+    #[test]
+    fn test_transform_ragged_grid() {
+        let grid = vec![vec![1, 2, 3], vec![4]];
+        assert_eq!(
+            Transform::Transpose.apply_grid(&grid),
+            vec![vec![1, 4], vec![2], vec![3]]
+        );
+        assert_eq!(
+            Transform::FlipHorizontal.apply_grid(&grid),
+            vec![vec![3, 2, 1], vec![4]]
+        );
+    }
+
+    #[test]
+    fn test_transform_puzzle() {
+        let grid = picture();
+        let (row_hints, col_hints) = generate_hints(&grid).unwrap();
+        let mut puzzle = PuzzleData {
+            version: "1.0".to_string(),
+            width: 4,
+            height: 3,
+            row_hints,
+            col_hints,
+            metadata: None,
+            solution: None,
+        };
+        puzzle.set_solution(&grid);
+
+        let rotated = Transform::Rotate270.apply_puzzle(&puzzle).unwrap();
+        assert_eq!((rotated.width, rotated.height), (3, 4));
+        assert_eq!(
+            rotated.solution_grid().unwrap(),
+            Some(Transform::Rotate270.apply_grid(&grid))
+        );
+
+        puzzle.solution = Some("!".to_string());
+        assert_eq!(
+            Transform::FlipVertical.apply_puzzle(&puzzle),
+            Err(FileError::InvalidSolution(SolutionError::Malformed))
+        );
+
+        // Unchecked data from the frontend; decoding its solution would
+        // build an endless number of rows
+        puzzle.width = 0;
+        puzzle.height = 100_000_000_000_000;
+        puzzle.solution = Some(String::new());
+        assert!(matches!(
+            Transform::FlipVertical.apply_puzzle(&puzzle),
+            Err(FileError::InvalidStructure { .. })
+        ));
+    }
+
+    #[test]
+    fn test_solver_answer_transforms_alike() {
+        let grid = picture();
+        let (rows, cols) = generate_hints(&grid).unwrap();
+        let solve = |rows: &[Vec<u32>], cols: &[Vec<u32>]| {
+            let puzzle = Puzzle::from_hints(rows.to_vec(), cols.to_vec()).unwrap();
+            let options = SolverOptions::default();
+            let (solutions, stopped) = backtrack_solve(&puzzle.unknown_grid(), &puzzle, &options);
+            assert_eq!(stopped, None);
+            solutions
+        };
+        let original = solve(&rows, &cols);
+        assert_eq!(original.len(), 1);
+
+        for transform in Transform::ALL {
+            let (t_rows, t_cols) = transform.apply_hints(&rows, &cols);
+            assert_eq!(
+                solve(&t_rows, &t_cols),
+                vec![transform.apply_grid(&original[0])],
+                "{:?}",
+                transform
+            );
+        }
+    }
+}
@@ -0,0 +1,201 @@
+//! The `illust_logic_solver` Python extension module.
+//!
+//! Hints are lists of lists of ints and grids are lists of rows of 0/1, so
+//! numpy arrays can be passed in and `numpy.array` accepts what comes out.
+//! Results and puzzle files are plain dicts with the same keys as the JSON
+//! the app exchanges, e.g. `{"type": "UniqueSolution", "grid": ..., "stats": ...}`.
+
+use std::path::PathBuf;
+
+use pyo3::exceptions::{PyOSError, PyTimeoutError, PyValueError};
+use pyo3::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::i18n::{Locale, Localize};
+use crate::puzzle::file_io::{self, FileError};
+use crate::puzzle::hint_generator;
+use crate::solver::backtrack::{self, backtrack_solve};
+use crate::solver::options::SolverOptions;
+use crate::solver::puzzle::Puzzle;
+use crate::solver::types::{SearchBudget, SolverSettings, ValidationError, GRID_SIZE_LIMIT};
+use crate::solver::validator::validate_puzzle;
+
+type Hints = Vec<Vec<u32>>;
+
+/// Solve a puzzle and return the result dict.
+#[pyfunction]
+#[pyo3(signature = (
+    row_hints,
+    col_hints,
+    *,
+    max_solutions = 2,
+    timeout_seconds = None,
+    max_nodes = None,
+    max_line_solver_calls = None,
+    locale = None,
+))]
+// Each argument is a parameter of the Python function
+#[allow(clippy::too_many_arguments)]
+fn solve(
+    py: Python<'_>,
+    row_hints: Hints,
+    col_hints: Hints,
+    max_solutions: usize,
+    timeout_seconds: Option<f64>,
+    max_nodes: Option<u64>,
+    max_line_solver_calls: Option<u64>,
+    locale: Option<&str>,
+) -> PyResult<PyObject> {
+    let locale = parse_locale(locale)?;
+    let puzzle = puzzle_from_hints(row_hints, col_hints, locale)?;
+    let options = SolverOptions {
+        max_solutions,
+        timeout_seconds,
+        budget: SearchBudget {
+            max_nodes,
+            max_line_solver_calls,
+        },
+        ..SolverOptions::default()
+    };
+    let result = py.allow_threads(|| backtrack::solve(&puzzle, &options));
+    to_python(py, &result)
+}
+
+/// Count the solutions, stopping at `limit` if given.
+/// Every solution found is kept until the count is done, so leave a limit
+/// on puzzles that may have very many. Raises `TimeoutError` if the timeout
+/// passes before the count is complete.
+#[pyfunction]
+#[pyo3(signature = (row_hints, col_hints, *, limit = None, timeout_seconds = None, locale = None))]
+fn count_solutions(
+    py: Python<'_>,
+    row_hints: Hints,
+    col_hints: Hints,
+    limit: Option<usize>,
+    timeout_seconds: Option<f64>,
+    locale: Option<&str>,
+) -> PyResult<usize> {
+    let locale = parse_locale(locale)?;
+    let puzzle = puzzle_from_hints(row_hints, col_hints, locale)?;
+    let options = SolverOptions {
+        max_solutions: limit.unwrap_or(usize::MAX),
+        timeout_seconds,
+        ..SolverOptions::default()
+    };
+    let (solutions, stopped) =
+        py.allow_threads(|| backtrack_solve(&puzzle.unknown_grid(), &puzzle, &options));
+    if stopped.is_some() {
+        let message = format!("timed out after {} solutions", solutions.len());
+        return Err(PyTimeoutError::new_err(message));
+    }
+    Ok(solutions.len())
+}
+
+/// Row and column hints of a picture whose nonzero cells are filled.
+/// A picture larger than any puzzle raises `ValueError`.
+#[pyfunction]
+#[pyo3(signature = (grid, *, locale = None))]
+fn generate_hints(grid: Vec<Vec<u8>>, locale: Option<&str>) -> PyResult<(Hints, Hints)> {
+    let locale = parse_locale(locale)?;
+    hint_generator::generate_hints(&grid).map_err(|errors| validation_error(&errors, locale))
+}
+
+/// Check hints against a size and return the validation result dict.
+/// `max_grid_size` defaults to the largest size the solver accepts.
+#[pyfunction]
+#[pyo3(signature = (width, height, row_hints, col_hints, *, max_grid_size = None, locale = None))]
+fn validate(
+    py: Python<'_>,
+    width: usize,
+    height: usize,
+    row_hints: Hints,
+    col_hints: Hints,
+    max_grid_size: Option<usize>,
+    locale: Option<&str>,
+) -> PyResult<PyObject> {
+    let locale = parse_locale(locale)?;
+    let settings = SolverSettings {
+        max_grid_size: max_grid_size.unwrap_or(GRID_SIZE_LIMIT),
+    };
+    let result = validate_puzzle(width, height, &row_hints, &col_hints, &settings);
+    to_python(py, &result.localized(locale))
+}
+
+/// Read a puzzle file of any supported version as a puzzle dict.
+#[pyfunction]
+#[pyo3(signature = (path, *, locale = None))]
+fn load_puzzle(py: Python<'_>, path: PathBuf, locale: Option<&str>) -> PyResult<PyObject> {
+    let locale = parse_locale(locale)?;
+    let puzzle = file_io::load_puzzle_file(&path).map_err(|e| file_error(e, locale))?;
+    to_python(py, &puzzle)
+}
+
+/// Check a puzzle dict and write it in the current file format.
+#[pyfunction]
+#[pyo3(signature = (path, puzzle, *, locale = None))]
+fn save_puzzle(
+    py: Python<'_>,
+    path: PathBuf,
+    puzzle: &Bound<'_, PyAny>,
+    locale: Option<&str>,
+) -> PyResult<()> {
+    let locale = parse_locale(locale)?;
+    let value: serde_json::Value = from_python(py, puzzle)?;
+    let puzzle = file_io::puzzle_from_value(value).map_err(|e| file_error(e, locale))?;
+    file_io::save_puzzle_file(&path, &puzzle).map_err(|e| file_error(e, locale))
+}
+
+#[pymodule]
+#[pyo3(name = "illust_logic_solver")]
+fn python_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(solve, m)?)?;
+    m.add_function(wrap_pyfunction!(count_solutions, m)?)?;
+    m.add_function(wrap_pyfunction!(generate_hints, m)?)?;
+    m.add_function(wrap_pyfunction!(validate, m)?)?;
+    m.add_function(wrap_pyfunction!(load_puzzle, m)?)?;
+    m.add_function(wrap_pyfunction!(save_puzzle, m)?)?;
+    Ok(())
+}
+
+/// `None` is the app's default language.
+fn parse_locale(locale: Option<&str>) -> PyResult<Locale> {
+    match locale {
+        None => Ok(Locale::default()),
+        Some(name) => serde_json::from_value(name.into())
+            .map_err(|_| PyValueError::new_err(format!("unknown locale: {:?}", name))),
+    }
+}
+
+/// Sized by the hints; invalid hints raise `ValueError` with every message.
+fn puzzle_from_hints(row_hints: Hints, col_hints: Hints, locale: Locale) -> PyResult<Puzzle> {
+    Puzzle::from_hints(row_hints, col_hints).map_err(|errors| validation_error(&errors, locale))
+}
+
+fn validation_error(errors: &[ValidationError], locale: Locale) -> PyErr {
+    let messages: Vec<String> = errors.iter().map(|e| e.localize(locale)).collect();
+    PyValueError::new_err(messages.join("\n"))
+}
+
+fn file_error(error: FileError, locale: Locale) -> PyErr {
+    let message = error.localize(locale);
+    match error {
+        FileError::Read(_) | FileError::Write(_) => PyOSError::new_err(message),
+        _ => PyValueError::new_err(message),
+    }
+}
+
+/// Python's own `json` module maps between dicts and the app's serde types,
+/// so both sides agree on the JSON layout by construction.
+fn to_python<T: Serialize>(py: Python<'_>, value: &T) -> PyResult<PyObject> {
+    let json = serde_json::to_string(value).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    Ok(py.import("json")?.call_method1("loads", (json,))?.unbind())
+}
+
+fn from_python<T: DeserializeOwned>(py: Python<'_>, value: &Bound<'_, PyAny>) -> PyResult<T> {
+    let json: String = py
+        .import("json")?
+        .call_method1("dumps", (value,))?
+        .extract()?;
+    serde_json::from_str(&json).map_err(|e| PyValueError::new_err(e.to_string()))
+}
@@ -0,0 +1,721 @@
+//! HTTP server for tools that call the solver on the same machine.
+//! Each endpoint takes a JSON body by POST and answers with JSON:
+//!
+//! | Path | Body | Answer |
+//! |------|------|--------|
+//! | `/solve` | `{"rowHints", "colHints", "options"?: SolverOptions}` | `SolveResult` |
+//! | `/rate` | same as `/solve` | `Rating` |
+//! | `/validate` | `{"width", "height", "rowHints", "colHints", "solverSettings"?}` | `ValidationResult` |
+//! | `/generate-hints` | `{"grid": [[0, 1, ...], ...]}` | `PuzzleData` with the grid as its solution |
+//! | `/convert` | a puzzle file of any supported version | `PuzzleData` in the current version |
+//!
+//! `?locale=en` picks the language of messages. Failures answer with an
+//! error status and `{"type": "NotFound", "message": "..."}`.
+
+use std::io::{self, Read};
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, SendError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tiny_http::{Header, Method, Request, Response};
+
+use crate::i18n::{Locale, Localize};
+use crate::puzzle::data::PuzzleData;
+use crate::puzzle::file_io::{self, FileError};
+use crate::puzzle::hint_generator::{generate_hints, picture_size};
+use crate::solver::backtrack::solve;
+use crate::solver::options::SolverOptions;
+use crate::solver::puzzle::Puzzle;
+use crate::solver::rating::{self, Rating};
+use crate::solver::types::{
+    SearchStats, SolveResult, SolveStats, SolverSettings, ValidationError, GRID_SIZE_LIMIT,
+};
+use crate::solver::validator::validate_puzzle;
+use crate::unwind;
+
+type Hints = Vec<Vec<u32>>;
+
+const USAGE: &str = "usage: illust-logic-server [--addr ADDRESS] [--max-concurrent N] \
+                     [--max-timeout SECONDS] [--locale ja|en]";
+
+/// Why a request got no answer from its endpoint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServerError {
+    NotFound(String),
+    MethodNotAllowed,
+    BodyTooLarge {
+        limit: usize,
+    },
+    /// The body did not arrive within `ServerConfig::body_timeout`.
+    BodyTimeout {
+        timeout: Duration,
+    },
+    /// The body or query could not be read; carries the parser's description.
+    InvalidRequest(String),
+    /// The grid sent to `/generate-hints` makes no valid puzzle.
+    InvalidPuzzle(Vec<ValidationError>),
+    /// The puzzle file sent to `/convert` could not be read.
+    File(FileError),
+    /// Every request slot is taken.
+    Busy,
+    /// A bug surfaced as a panic; carries the panic message.
+    Internal(String),
+}
+
+impl ServerError {
+    fn status(&self) -> u16 {
+        match self {
+            ServerError::NotFound(_) => 404,
+            ServerError::MethodNotAllowed => 405,
+            ServerError::BodyTooLarge { .. } => 413,
+            ServerError::BodyTimeout { .. } => 408,
+            ServerError::InvalidRequest(_) => 400,
+            ServerError::InvalidPuzzle(_) | ServerError::File(_) => 422,
+            ServerError::Busy => 503,
+            ServerError::Internal(_) => 500,
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            ServerError::NotFound(_) => "NotFound",
+            ServerError::MethodNotAllowed => "MethodNotAllowed",
+            ServerError::BodyTooLarge { .. } => "BodyTooLarge",
+            ServerError::BodyTimeout { .. } => "BodyTimeout",
+            ServerError::InvalidRequest(_) => "InvalidRequest",
+            ServerError::InvalidPuzzle(_) => "InvalidPuzzle",
+            ServerError::File(_) => "InvalidFile",
+            ServerError::Busy => "Busy",
+            ServerError::Internal(_) => "Internal",
+        }
+    }
+}
+
+/// Limits that keep one client from tying up the machine.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerConfig {
+    /// Requests worked on at once; more are answered with `Busy`.
+    pub max_concurrent: usize,
+    /// Longest a solve may run. Requests may ask for less, never for more.
+    pub max_timeout: Duration,
+    /// Most solutions a solve keeps.
+    pub max_solutions: usize,
+    pub max_body_bytes: usize,
+    /// Longest a client may take to send a body. A slow client's request
+    /// slot is freed once this passes.
+    pub body_timeout: Duration,
+    /// Language of messages for requests without `?locale=`.
+    pub locale: Locale,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
+            max_concurrent: thread::available_parallelism().map_or(1, |n| n.get()),
+            max_timeout: Duration::from_secs(30),
+            max_solutions: 100,
+            // Enough for a grid at GRID_SIZE_LIMIT written out as JSON
+            max_body_bytes: 8 * 1024 * 1024,
+            body_timeout: Duration::from_secs(10),
+            locale: Locale::default(),
+        }
+    }
+}
+
+impl ServerConfig {
+    /// `options` within the server's limits. Without a timeout of its own a
+    /// solve gets the longest allowed.
+    fn limit(&self, mut options: SolverOptions) -> SolverOptions {
+        let max_timeout = self.max_timeout.as_secs_f64();
+        options.timeout_seconds = Some(
+            options
+                .timeout_seconds
+                .map_or(max_timeout, |timeout| timeout.min(max_timeout)),
+        );
+        options.max_solutions = options.max_solutions.min(self.max_solutions);
+        options
+    }
+}
+
+pub struct Server {
+    http: tiny_http::Server,
+    config: Arc<ServerConfig>,
+    active: Arc<AtomicUsize>,
+}
+
+impl Server {
+    /// Listen on `addr`; port 0 picks a free one, see `local_addr`.
+    pub fn bind(addr: impl ToSocketAddrs, config: ServerConfig) -> io::Result<Server> {
+        let http = tiny_http::Server::http(addr).map_err(io::Error::other)?;
+        Ok(Server {
+            http,
+            config: Arc::new(config),
+            active: Arc::new(AtomicUsize::new(0)),
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.http
+            .server_addr()
+            .to_ip()
+            .expect("bound to an IP address")
+    }
+
+    /// Answer requests, each on its own thread, until the listener fails.
+    pub fn run(self) {
+        for request in self.http.incoming_requests() {
+            let Some(slot) = Slot::take(&self.active, self.config.max_concurrent) else {
+                respond(request, Err(ServerError::Busy), self.config.locale);
+                continue;
+            };
+            let config = Arc::clone(&self.config);
+            thread::spawn(move || {
+                let _slot = slot;
+                handle(request, &config);
+            });
+        }
+    }
+}
+
+/// Entry point of the `illust-logic-server` binary.
+pub fn run_cli(args: impl Iterator<Item = String>) -> ExitCode {
+    let (addr, config) = match parse_args(args) {
+        Ok(Some(parsed)) => parsed,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(message) => {
+            eprintln!("{}\n{}", message, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    let server = match Server::bind(&addr, config) {
+        Ok(server) => server,
+        Err(e) => {
+            eprintln!("cannot listen on {}: {}", addr, e);
+            return ExitCode::FAILURE;
+        }
+    };
+    eprintln!("listening on http://{}", server.local_addr());
+    server.run();
+    ExitCode::FAILURE
+}
+
+/// The address and limits given on the command line; `None` asks for help.
+fn parse_args(
+    mut args: impl Iterator<Item = String>,
+) -> Result<Option<(String, ServerConfig)>, String> {
+    let mut addr = "127.0.0.1:7878".to_string();
+    let mut config = ServerConfig::default();
+    while let Some(flag) = args.next() {
+        if flag == "-h" || flag == "--help" {
+            return Ok(None);
+        }
+        let value = args
+            .next()
+            .ok_or_else(|| format!("{} needs a value", flag))?;
+        let invalid = || format!("invalid value for {}: {}", flag, value);
+        match flag.as_str() {
+            "--addr" => addr = value,
+            "--max-concurrent" => config.max_concurrent = value.parse().map_err(|_| invalid())?,
+            "--max-timeout" => {
+                let seconds: f64 = value.parse().map_err(|_| invalid())?;
+                config.max_timeout = Duration::try_from_secs_f64(seconds).map_err(|_| invalid())?;
+            }
+            "--locale" => config.locale = parse_locale(&value).map_err(|_| invalid())?,
+            _ => return Err(format!("unknown option: {}", flag)),
+        }
+    }
+    Ok(Some((addr, config)))
+}
+
+/// One of `max_concurrent` places for a request being worked on; freed on drop.
+struct Slot(Arc<AtomicUsize>);
+
+impl Slot {
+    fn take(active: &Arc<AtomicUsize>, max: usize) -> Option<Slot> {
+        active
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+                (n < max).then_some(n + 1)
+            })
+            .ok()?;
+        Some(Slot(Arc::clone(active)))
+    }
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SolveRequest {
+    row_hints: Hints,
+    col_hints: Hints,
+    #[serde(default)]
+    options: SolverOptions,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ValidateRequest {
+    width: usize,
+    height: usize,
+    row_hints: Hints,
+    col_hints: Hints,
+    /// Defaults to the largest size the solver accepts.
+    solver_settings: Option<SolverSettings>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GenerateHintsRequest {
+    grid: Vec<Vec<u8>>,
+}
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    message: String,
+}
+
+fn handle(request: Request, config: &ServerConfig) {
+    let url = request.url().to_string();
+    let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+    let locale = match query_locale(query) {
+        Ok(locale) => locale.unwrap_or(config.locale),
+        Err(error) => return respond(request, Err(error), config.locale),
+    };
+    if let Err(error) = check_endpoint(&request, path) {
+        return respond(request, Err(error), locale);
+    }
+    let Some((request, body)) = read_body_within(request, config, locale) else {
+        return;
+    };
+    let answer = body.and_then(|body| {
+        unwind::catch(|| route(path, &body, config, locale))
+            .unwrap_or_else(|message| Err(ServerError::Internal(message)))
+    });
+    respond(request, answer, locale);
+}
+
+fn check_endpoint(request: &Request, path: &str) -> Result<(), ServerError> {
+    const ENDPOINTS: [&str; 5] = [
+        "/solve",
+        "/rate",
+        "/validate",
+        "/generate-hints",
+        "/convert",
+    ];
+    if !ENDPOINTS.contains(&path) {
+        return Err(ServerError::NotFound(path.to_string()));
+    }
+    if *request.method() != Method::Post {
+        return Err(ServerError::MethodNotAllowed);
+    }
+    Ok(())
+}
+
+/// The JSON answer of the endpoint at `path`.
+fn route(
+    path: &str,
+    body: &str,
+    config: &ServerConfig,
+    locale: Locale,
+) -> Result<String, ServerError> {
+    match path {
+        "/solve" => {
+            let SolveRequest {
+                row_hints,
+                col_hints,
+                options,
+            } = parse_body(body)?;
+            let result = match checked_puzzle(row_hints, col_hints, locale) {
+                Ok(puzzle) => solve(&puzzle, &config.limit(options)),
+                Err(invalid) => invalid,
+            };
+            to_json(&result)
+        }
+        "/rate" => {
+            let SolveRequest {
+                row_hints,
+                col_hints,
+                options,
+            } = parse_body(body)?;
+            let rating = match checked_puzzle(row_hints, col_hints, locale) {
+                Ok(puzzle) => rating::rate(&puzzle, &config.limit(options)),
+                Err(invalid) => Rating::new(invalid),
+            };
+            to_json(&rating)
+        }
+        "/validate" => {
+            let request: ValidateRequest = parse_body(body)?;
+            let settings = request.solver_settings.unwrap_or(SolverSettings {
+                max_grid_size: GRID_SIZE_LIMIT,
+            });
+            let result = validate_puzzle(
+                request.width,
+                request.height,
+                &request.row_hints,
+                &request.col_hints,
+                &settings,
+            );
+            to_json(&result.localized(locale))
+        }
+        "/generate-hints" => {
+            let GenerateHintsRequest { grid } = parse_body(body)?;
+            to_json(&puzzle_from_grid(grid)?)
+        }
+        "/convert" => to_json(&file_io::parse_puzzle(body).map_err(ServerError::File)?),
+        _ => unreachable!("checked by check_endpoint"),
+    }
+}
+
+/// Like the app's solve command, malformed hints make an `InvalidInput`
+/// result rather than an error.
+fn checked_puzzle(
+    row_hints: Hints,
+    col_hints: Hints,
+    locale: Locale,
+) -> Result<Puzzle, SolveResult> {
+    let started = Instant::now();
+    Puzzle::from_hints(row_hints, col_hints).map_err(|errors| {
+        let stats = SolveStats::new(SearchStats::default(), started.elapsed());
+        SolveResult::invalid_input(errors, locale, stats)
+    })
+}
+
+/// A puzzle file for the picture in `grid`, nonzero cells being filled.
+/// Rows shorter than the longest are read as ending in empty cells.
+fn puzzle_from_grid(grid: Vec<Vec<u8>>) -> Result<PuzzleData, ServerError> {
+    // Before padding, which would build every row at the longest one's length
+    let (width, height) = picture_size(&grid).map_err(ServerError::InvalidPuzzle)?;
+    let grid: Vec<Vec<u8>> = grid
+        .iter()
+        .map(|row| {
+            let mut cells: Vec<u8> = row.iter().map(|&cell| u8::from(cell != 0)).collect();
+            cells.resize(width, 0);
+            cells
+        })
+        .collect();
+    let (row_hints, col_hints) = generate_hints(&grid).map_err(ServerError::InvalidPuzzle)?;
+    let puzzle =
+        Puzzle::new(width, height, row_hints, col_hints).map_err(ServerError::InvalidPuzzle)?;
+    let mut data = PuzzleData::from(&puzzle);
+    data.set_solution(&grid);
+    Ok(data)
+}
+
+/// Read the body on a thread of its own and wait for it at most
+/// `config.body_timeout`, so that a client that stops sending cannot keep
+/// its request slot. `None` if the time ran out; the reading thread then
+/// answers `BodyTimeout` itself once the read ends.
+fn read_body_within(
+    request: Request,
+    config: &ServerConfig,
+    locale: Locale,
+) -> Option<(Request, Result<String, ServerError>)> {
+    let (sender, receiver) = mpsc::channel();
+    let (limit, timeout) = (config.max_body_bytes, config.body_timeout);
+    thread::spawn(move || {
+        let mut request = request;
+        let body = read_body(&mut request, limit);
+        if let Err(SendError((request, _))) = sender.send((request, body)) {
+            respond(request, Err(ServerError::BodyTimeout { timeout }), locale);
+        }
+    });
+    receiver.recv_timeout(timeout).ok()
+}
+
+/// The body as text, refused once it passes `limit` bytes.
+fn read_body(request: &mut Request, limit: usize) -> Result<String, ServerError> {
+    if request.body_length().is_some_and(|length| length > limit) {
+        return Err(ServerError::BodyTooLarge { limit });
+    }
+    let mut body = Vec::new();
+    request
+        .as_reader()
+        .take(limit as u64 + 1)
+        .read_to_end(&mut body)
+        .map_err(|e| ServerError::InvalidRequest(e.to_string()))?;
+    if body.len() > limit {
+        return Err(ServerError::BodyTooLarge { limit });
+    }
+    String::from_utf8(body).map_err(|e| ServerError::InvalidRequest(e.to_string()))
+}
+
+fn parse_body<T: for<'de> Deserialize<'de>>(body: &str) -> Result<T, ServerError> {
+    serde_json::from_str(body).map_err(|e| ServerError::InvalidRequest(e.to_string()))
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, ServerError> {
+    serde_json::to_string(value).map_err(|e| ServerError::Internal(e.to_string()))
+}
+
+fn query_locale(query: &str) -> Result<Option<Locale>, ServerError> {
+    let Some(name) = query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("locale="))
+    else {
+        return Ok(None);
+    };
+    parse_locale(name)
+        .map(Some)
+        .map_err(|_| ServerError::InvalidRequest(format!("unknown locale: {}", name)))
+}
+
+fn parse_locale(name: &str) -> serde_json::Result<Locale> {
+    serde_json::from_value(name.into())
+}
+
+/// Send the answer; a client that has gone away is not an error of ours.
+fn respond(request: Request, answer: Result<String, ServerError>, locale: Locale) {
+    let (status, json) = match answer {
+        Ok(json) => (200, json),
+        Err(error) => {
+            let body = ErrorResponse {
+                kind: error.kind(),
+                message: error.localize(locale),
+            };
+            let json = serde_json::to_string(&body).expect("error responses serialize");
+            (error.status(), json)
+        }
+    };
+    let content_type = Header::from_bytes("Content-Type", "application/json; charset=utf-8")
+        .expect("valid header");
+    let response = Response::from_string(json)
+        .with_status_code(status)
+        .with_header(content_type);
+    let _ = request.respond(response);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::net::TcpStream;
+
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::puzzle::migration::CURRENT_VERSION;
+
+    fn start(config: ServerConfig) -> SocketAddr {
+        let server = Server::bind("127.0.0.1:0", config).unwrap();
+        let addr = server.local_addr();
+        thread::spawn(move || server.run());
+        addr
+    }
+
+    /// Send one request and return the status and the parsed JSON answer.
+    fn send(addr: SocketAddr, method: &str, path: &str, body: &str) -> (u16, Value) {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(
+            stream,
+            "{} {} HTTP/1.1\r\nHost: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            method,
+            path,
+            addr,
+            body.len(),
+            body
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+        (status, serde_json::from_str(body).unwrap())
+    }
+
+    fn post(addr: SocketAddr, path: &str, body: Value) -> (u16, Value) {
+        send(addr, "POST", path, &body.to_string())
+    }
+
+    #[test]
+    fn test_endpoints() {
+        let addr = start(ServerConfig::default());
+        let cross = json!([[1], [3], [1]]);
+
+        let (status, result) = post(
+            addr,
+            "/solve",
+            json!({"rowHints": cross, "colHints": cross, "options": {"timeoutSeconds": 10}}),
+        );
+        assert_eq!(status, 200);
+        assert_eq!(result["type"], "UniqueSolution");
+        assert_eq!(result["grid"], json!([[0, 1, 0], [1, 1, 1], [0, 1, 0]]));
+
+        let (_, result) = post(
+            addr,
+            "/solve?locale=en",
+            json!({"rowHints": [[3]], "colHints": [[1]]}),
+        );
+        assert_eq!(result["type"], "InvalidInput");
+        assert_eq!(
+            result["messages"][0],
+            "Row 1 needs at least 3 cells, but the width is 1"
+        );
+
+        let (_, rating) = post(addr, "/rate", json!({"rowHints": cross, "colHints": cross}));
+        assert_eq!(rating["difficulty"], "LineLogic");
+        assert_eq!(rating["result"]["type"], "UniqueSolution");
+
+        let (_, result) = post(
+            addr,
+            "/validate",
+            json!({"width": 2, "height": 1, "rowHints": [[1]], "colHints": [[1], [1]]}),
+        );
+        assert_eq!(result["valid"], false);
+
+        let (_, puzzle) = post(addr, "/generate-hints", json!({"grid": [[1, 1], [0]]}));
+        let puzzle: PuzzleData = serde_json::from_value(puzzle).unwrap();
+        assert_eq!(
+            (puzzle.row_hints, puzzle.col_hints),
+            (vec![vec![2], vec![]], vec![vec![1]; 2])
+        );
+        assert!(puzzle.solution.is_some());
+
+        let old = include_str!("puzzle/testdata/v1_0.json");
+        let (status, puzzle) = send(addr, "POST", "/convert", old);
+        assert_eq!(status, 200);
+        assert_eq!(puzzle["version"], CURRENT_VERSION);
+    }
+
+    #[test]
+    fn test_errors() {
+        let addr = start(ServerConfig {
+            max_body_bytes: 100,
+            ..ServerConfig::default()
+        });
+
+        let (status, error) = send(addr, "POST", "/unknown", "");
+        assert_eq!((status, &error["type"]), (404, &json!("NotFound")));
+        assert_eq!(send(addr, "GET", "/solve", "").0, 405);
+        let (status, error) = send(addr, "POST", "/solve?locale=en", "{");
+        assert_eq!((status, &error["type"]), (400, &json!("InvalidRequest")));
+        assert!(error["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("Invalid request"));
+        assert_eq!(send(addr, "POST", "/solve?locale=xx", "{}").0, 400);
+        assert_eq!(send(addr, "POST", "/convert", &"x".repeat(101)).0, 413);
+        assert_eq!(
+            send(addr, "POST", "/convert", r#"{"version": "9.0"}"#).0,
+            422
+        );
+        assert_eq!(post(addr, "/generate-hints", json!({"grid": []})).0, 422);
+    }
+
+    #[test]
+    fn test_oversized_grid_is_refused_before_padding() {
+        // Padded, this would be a million rows of GRID_SIZE_LIMIT + 1 cells
+        let mut grid = vec![Vec::new(); 1_000_000];
+        grid[0] = vec![1; GRID_SIZE_LIMIT + 1];
+        let Err(ServerError::InvalidPuzzle(errors)) = puzzle_from_grid(grid) else {
+            panic!("expected InvalidPuzzle");
+        };
+        let values: Vec<usize> = errors
+            .iter()
+            .map(|e| match e {
+                ValidationError::DimensionOutOfRange { value, .. } => *value,
+                _ => panic!("unexpected error {:?}", e),
+            })
+            .collect();
+        assert_eq!(values, vec![GRID_SIZE_LIMIT + 1, 1_000_000]);
+    }
+
+    #[test]
+    fn test_requests_stay_within_limits() {
+        let config = ServerConfig {
+            max_timeout: Duration::ZERO,
+            max_solutions: 3,
+            ..ServerConfig::default()
+        };
+        let options = config.limit(SolverOptions::new().max_solutions(1000));
+        assert_eq!(options.timeout_seconds, Some(0.0));
+        assert_eq!(options.max_solutions, 3);
+
+        // Already out of time before the first guess
+        let addr = start(config);
+        let hints = json!([[1], [1]]);
+        let (_, result) = post(
+            addr,
+            "/solve",
+            json!({"rowHints": hints, "colHints": hints}),
+        );
+        assert_eq!(result["type"], "Timeout");
+    }
+
+    #[test]
+    fn test_concurrency_slots() {
+        let active = Arc::new(AtomicUsize::new(0));
+        let first = Slot::take(&active, 2).unwrap();
+        let _second = Slot::take(&active, 2).unwrap();
+        assert!(Slot::take(&active, 2).is_none());
+        drop(first);
+        assert!(Slot::take(&active, 2).is_some());
+        assert!(Slot::take(&active, 0).is_none());
+    }
+
+    #[test]
+    fn test_slow_body_frees_its_slot() {
+        let addr = start(ServerConfig {
+            max_concurrent: 1,
+            body_timeout: Duration::from_millis(100),
+            ..ServerConfig::default()
+        });
+        // The headers promise a body that only comes after the timeout. It is
+        // long enough that tiny_http leaves reading it to the handler.
+        let body = format!("{:<2048}", r#"{"grid": [[1]]}"#);
+        let mut slow = TcpStream::connect(addr).unwrap();
+        write!(
+            slow,
+            "POST /generate-hints HTTP/1.1\r\nHost: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            addr,
+            body.len()
+        )
+        .unwrap();
+        thread::sleep(Duration::from_millis(300));
+
+        // The only slot is free again
+        assert_eq!(post(addr, "/generate-hints", json!({"grid": [[1]]})).0, 200);
+
+        // The late body is answered without being worked on
+        slow.write_all(body.as_bytes()).unwrap();
+        let mut response = String::new();
+        slow.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 408"), "{}", response);
+        assert!(response.contains("BodyTimeout"), "{}", response);
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let args = |list: &[&str]| parse_args(list.iter().map(|s| s.to_string()));
+        let (addr, config) = args(&[
+            "--addr",
+            "127.0.0.1:0",
+            "--max-timeout",
+            "1.5",
+            "--locale",
+            "en",
+        ])
+        .unwrap()
+        .unwrap();
+        assert_eq!(addr, "127.0.0.1:0");
+        assert_eq!(config.max_timeout, Duration::from_millis(1500));
+        assert_eq!(config.locale, Locale::En);
+
+        assert!(args(&["--help"]).unwrap().is_none());
+        assert!(args(&["--max-concurrent"]).is_err());
+        assert!(args(&["--max-concurrent", "many"]).is_err());
+        assert!(args(&["--max-timeout", "-1"]).is_err());
+        assert!(args(&["--port", "80"]).is_err());
+    }
+}
@@ -0,0 +1,257 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::i18n::Locale;
+use crate::solver::types::{SolverSettings, GRID_SIZE_LIMIT};
+
+/// Schema version written by this build.
+pub const SETTINGS_VERSION: u32 = 1;
+
+pub const SETTINGS_FILE_NAME: &str = "settings.json";
+
+/// Timeout range from spec section 3.2.2.
+const TIMEOUT_RANGE: (u64, u64) = (10, 300);
+const GRID_SIZE_RANGE: (usize, usize) = (1, GRID_SIZE_LIMIT);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GridLineThickness {
+    Thin,
+    Normal,
+    Thick,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CellSize {
+    Small,
+    Medium,
+    Large,
+}
+
+/// Application settings from spec section 3.5, persisted as JSON.
+/// Missing fields take their default value when loading.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AppSettings {
+    pub version: u32,
+    pub timeout_seconds: u64,
+    pub max_grid_size: usize,
+    pub show_size_warning: bool,
+    pub grid_line_thickness: GridLineThickness,
+    pub cell_size: CellSize,
+    pub locale: Locale,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        AppSettings {
+            version: SETTINGS_VERSION,
+            timeout_seconds: 60,
+            max_grid_size: SolverSettings::default().max_grid_size,
+            show_size_warning: true,
+            grid_line_thickness: GridLineThickness::Normal,
+            cell_size: CellSize::Medium,
+            locale: Locale::default(),
+        }
+    }
+}
+
+impl AppSettings {
+    pub fn solver_settings(&self) -> SolverSettings {
+        SolverSettings {
+            max_grid_size: self.max_grid_size,
+        }
+    }
+
+    /// Stamp the current version and clamp values into their allowed ranges.
+    pub fn normalized(mut self) -> Self {
+        self.version = SETTINGS_VERSION;
+        self.timeout_seconds = self.timeout_seconds.clamp(TIMEOUT_RANGE.0, TIMEOUT_RANGE.1);
+        self.max_grid_size = self.max_grid_size.clamp(GRID_SIZE_RANGE.0, GRID_SIZE_RANGE.1);
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SettingsError {
+    Read(String),
+    InvalidJson(String),
+    /// The file was written by a newer version of the app.
+    UnsupportedVersion { found: u64, supported: u32 },
+    Serialize(String),
+    Write(String),
+}
+
+/// Upgrade steps; `MIGRATIONS[i]` turns a version `i` object into version `i + 1`.
+const MIGRATIONS: [fn(&mut Map<String, Value>); SETTINGS_VERSION as usize] = [migrate_v0_to_v1];
+
+/// Version 0 is any file written before settings carried a `version` field.
+/// Its keys already match version 1; values outside the allowed ranges are
+/// clamped by `normalized` afterwards.
+fn migrate_v0_to_v1(settings: &mut Map<String, Value>) {
+    settings.insert("version".to_string(), Value::from(1));
+}
+
+/// Parse a settings file, applying migrations from older schema versions.
+pub fn parse_settings(content: &str) -> Result<AppSettings, SettingsError> {
+    let value: Value =
+        serde_json::from_str(content).map_err(|e| SettingsError::InvalidJson(e.to_string()))?;
+    let Value::Object(mut object) = value else {
+        return Err(SettingsError::InvalidJson(
+            "expected a JSON object".to_string(),
+        ));
+    };
+
+    let found = match object.get("version") {
+        None => 0,
+        Some(v) => v
+            .as_u64()
+            .ok_or_else(|| SettingsError::InvalidJson(format!("invalid version: {}", v)))?,
+    };
+    if found > SETTINGS_VERSION as u64 {
+        return Err(SettingsError::UnsupportedVersion {
+            found,
+            supported: SETTINGS_VERSION,
+        });
+    }
+    for migrate in &MIGRATIONS[found as usize..] {
+        migrate(&mut object);
+    }
+
+    let settings: AppSettings = serde_json::from_value(Value::Object(object))
+        .map_err(|e| SettingsError::InvalidJson(e.to_string()))?;
+    Ok(settings.normalized())
+}
+
+/// Load settings from `path`; a missing file yields the defaults.
+pub fn load_settings(path: &Path) -> Result<AppSettings, SettingsError> {
+    if !path.exists() {
+        return Ok(AppSettings::default());
+    }
+    let content = fs::read_to_string(path).map_err(|e| SettingsError::Read(e.to_string()))?;
+    parse_settings(&content)
+}
+
+pub fn save_settings(path: &Path, settings: &AppSettings) -> Result<(), SettingsError> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| SettingsError::Write(e.to_string()))?;
+    }
+    let json = serde_json::to_string_pretty(settings)
+        .map_err(|e| SettingsError::Serialize(e.to_string()))?;
+    fs::write(path, json).map_err(|e| SettingsError::Write(e.to_string()))?;
+    Ok(())
+}
+
+/// Settings shared between commands, backed by a file in the app config directory.
+pub struct SettingsState {
+    path: PathBuf,
+    settings: Mutex<AppSettings>,
+}
+
+impl SettingsState {
+    /// Load the stored settings, falling back to the defaults when the file
+    /// cannot be used. An unusable file is left untouched until the next update.
+    pub fn load(path: PathBuf) -> Self {
+        let settings = load_settings(&path).unwrap_or_default();
+        SettingsState {
+            path,
+            settings: Mutex::new(settings),
+        }
+    }
+
+    // A panic while holding the lock cannot leave the settings half-written,
+    // so a poisoned lock is still safe to use.
+    pub fn get(&self) -> AppSettings {
+        self.settings
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Normalize, persist and return the new settings.
+    pub fn update(&self, settings: AppSettings) -> Result<AppSettings, SettingsError> {
+        let settings = settings.normalized();
+        let mut current = self
+            .settings
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        save_settings(&self.path, &settings)?;
+        *current = settings.clone();
+        Ok(settings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_settings_path(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("illust-logic-settings-{}-{}", std::process::id(), name))
+            .join(SETTINGS_FILE_NAME)
+    }
+
+    #[test]
+    fn test_missing_file_gives_defaults() {
+        let path = temp_settings_path("missing");
+        assert_eq!(load_settings(&path).unwrap(), AppSettings::default());
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let path = temp_settings_path("roundtrip");
+        let settings = AppSettings {
+            timeout_seconds: 120,
+            max_grid_size: 150,
+            cell_size: CellSize::Large,
+            locale: Locale::En,
+            ..AppSettings::default()
+        };
+        save_settings(&path, &settings).unwrap();
+        assert_eq!(load_settings(&path).unwrap(), settings);
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_migrate_unversioned_file() {
+        let settings =
+            parse_settings(r#"{"timeoutSeconds": 5, "cellSize": "small", "unknownKey": 1}"#)
+                .unwrap();
+        assert_eq!(settings.version, SETTINGS_VERSION);
+        assert_eq!(settings.timeout_seconds, 10); // Clamped into range
+        assert_eq!(settings.cell_size, CellSize::Small);
+        assert_eq!(settings.max_grid_size, 100);
+    }
+
+    #[test]
+    fn test_reject_newer_version() {
+        let result = parse_settings(r#"{"version": 99}"#);
+        assert_eq!(
+            result,
+            Err(SettingsError::UnsupportedVersion {
+                found: 99,
+                supported: SETTINGS_VERSION,
+            })
+        );
+    }
+
+    #[test]
+    fn test_state_update_persists() {
+        let path = temp_settings_path("state");
+        let state = SettingsState::load(path.clone());
+        let updated = state
+            .update(AppSettings {
+                timeout_seconds: 1000,
+                ..state.get()
+            })
+            .unwrap();
+        assert_eq!(updated.timeout_seconds, 300);
+        assert_eq!(SettingsState::load(path.clone()).get(), updated);
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
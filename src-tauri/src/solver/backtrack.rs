@@ -1,106 +1,198 @@
 use std::time::Instant;
 
 use super::logical_solver::logical_solve;
-use super::types::{CellState, Grid};
+use super::options::SolverOptions;
+use super::puzzle::Puzzle;
+use super::types::{
+    CellState, Grid, LimitReached, SearchStats, SolveResult, SolveStats, SolverSettings,
+};
+use crate::i18n::Locale;
+
+/// A branching point whose Empty alternative has not been tried yet.
+struct Decision {
+    row: usize,
+    col: usize,
+    /// Trail length before the Filled guess was made.
+    trail_len: usize,
+}
 
 /// Find solutions using backtracking with logical deduction.
-/// Stops after finding `max_solutions` solutions.
-/// Returns (solutions_found, timed_out).
+/// Stops after finding `options.max_solutions` solutions, or early at the
+/// options' deadline or budget.
+/// Returns the solutions found and the limit that cut the search short, if any.
+/// A grid of a different size than the puzzle has no solutions.
+///
+/// The search works on a single grid and undoes deductions through a trail of
+/// changed cells, so memory stays linear in the number of cells however deep
+/// the search goes.
 pub fn backtrack_solve(
     grid: &Grid,
-    row_hints: &[Vec<u32>],
-    col_hints: &[Vec<u32>],
-    max_solutions: usize,
-    deadline: Option<Instant>,
-) -> (Vec<Grid>, bool) {
-    let mut solutions = Vec::new();
-    let mut timed_out = false;
-    backtrack_recursive(
-        grid.clone(),
-        row_hints,
-        col_hints,
-        max_solutions,
-        deadline,
-        &mut solutions,
-        &mut timed_out,
-    );
-    (solutions, timed_out)
+    puzzle: &Puzzle,
+    options: &SolverOptions,
+) -> (Vec<Grid>, Option<LimitReached>) {
+    let (solutions, stopped, _) = backtrack_solve_with_stats(grid, puzzle, options);
+    (solutions, stopped)
 }
 
-fn backtrack_recursive(
-    mut grid: Grid,
+/// Solve the puzzle from scratch and report the outcome as the app shows it.
+/// The statistics include the time taken.
+///
+/// At least two solutions are looked for, whatever `options.max_solutions`
+/// says, so that a unique solution is only reported once proven unique.
+/// A search stopped by a limit before that reports the limit, even if it
+/// found a solution: claiming uniqueness would depend on where the limit
+/// happened to fall.
+pub fn solve(puzzle: &Puzzle, options: &SolverOptions) -> SolveResult {
+    let started = Instant::now();
+    let options = SolverOptions {
+        max_solutions: options.max_solutions.max(2),
+        ..options.clone()
+    };
+    let (solutions, stopped, search) =
+        backtrack_solve_with_stats(&puzzle.unknown_grid(), puzzle, &options);
+    let stats = SolveStats::new(search, started.elapsed());
+
+    let undecided = solutions.len() < 2;
+    match stopped {
+        Some(LimitReached::Deadline) if undecided => SolveResult::Timeout { stats },
+        Some(LimitReached::Budget) if undecided => SolveResult::BudgetExhausted { stats },
+        _ => match solutions.len() {
+            0 => SolveResult::NoSolution { stats },
+            1 => SolveResult::UniqueSolution {
+                grid: grid_to_u8(&solutions[0]),
+                stats,
+            },
+            _ => SolveResult::MultipleSolutions {
+                grids: solutions.iter().map(grid_to_u8).collect(),
+                stats,
+            },
+        },
+    }
+}
+
+/// Check the hints under `settings`, sizing the puzzle by them, and solve.
+/// Rejected hints come back as `SolveResult::InvalidInput` with messages in
+/// `locale`, without solving.
+pub fn solve_hints(
     row_hints: &[Vec<u32>],
     col_hints: &[Vec<u32>],
-    max_solutions: usize,
-    deadline: Option<Instant>,
-    solutions: &mut Vec<Grid>,
-    timed_out: &mut bool,
-) {
-    // Check timeout
-    if let Some(dl) = deadline {
-        if Instant::now() >= dl {
-            *timed_out = true;
-            return;
+    options: &SolverOptions,
+    settings: &SolverSettings,
+    locale: Locale,
+) -> SolveResult {
+    let started = Instant::now();
+    match Puzzle::with_settings(
+        col_hints.len(),
+        row_hints.len(),
+        row_hints.to_vec(),
+        col_hints.to_vec(),
+        settings,
+    ) {
+        Ok(puzzle) => solve(&puzzle, options),
+        Err(errors) => {
+            let stats = SolveStats::new(SearchStats::default(), started.elapsed());
+            SolveResult::invalid_input(errors, locale, stats)
         }
     }
+}
 
-    if solutions.len() >= max_solutions {
-        return;
+/// Same as `backtrack_solve`, also reporting how much searching it took.
+pub fn backtrack_solve_with_stats(
+    grid: &Grid,
+    puzzle: &Puzzle,
+    options: &SolverOptions,
+) -> (Vec<Grid>, Option<LimitReached>, SearchStats) {
+    let max_solutions = options.max_solutions;
+    let deadline = options.deadline_from(Instant::now());
+    let mut grid = grid.clone();
+    let mut solutions = Vec::new();
+    let mut trail: Vec<(usize, usize)> = Vec::new();
+    let mut decisions: Vec<Decision> = Vec::new();
+    let mut stats = SearchStats::default();
+
+    let fits_puzzle =
+        grid.len() == puzzle.height() && grid.iter().all(|row| row.len() == puzzle.width());
+    if max_solutions == 0 || !fits_puzzle {
+        return (solutions, None, stats);
     }
 
-    // Apply logical deduction
-    match logical_solve(&mut grid, row_hints, col_hints, deadline) {
-        Err(()) => return, // Contradiction
-        Ok(true) => {
-            // Fully solved
-            solutions.push(grid);
-            return;
+    loop {
+        if is_past(deadline) {
+            return (solutions, Some(LimitReached::Deadline), stats);
+        }
+        if options.budget.is_spent(&stats) {
+            return (solutions, Some(LimitReached::Budget), stats);
         }
-        Ok(false) => {
-            // Check timeout after logical solve
-            if let Some(dl) = deadline {
-                if Instant::now() >= dl {
-                    *timed_out = true;
-                    return;
+
+        // Apply logical deduction
+        stats.nodes += 1;
+        let deduced = logical_solve(
+            &mut grid,
+            puzzle,
+            deadline,
+            &mut trail,
+            &mut stats.line_solver_calls,
+        );
+        if stats.nodes == 1 {
+            stats.cells_by_logic = trail.len() as u64;
+        }
+        match deduced {
+            Err(()) => {} // Contradiction
+            Ok(true) => {
+                // Fully solved
+                if solutions.is_empty() {
+                    stats.cells_by_search = trail.len() as u64 - stats.cells_by_logic;
+                }
+                solutions.push(grid.clone());
+                if solutions.len() >= max_solutions {
+                    return (solutions, None, stats);
+                }
+            }
+            Ok(false) => {
+                // Check timeout after logical solve
+                if is_past(deadline) {
+                    return (solutions, Some(LimitReached::Deadline), stats);
+                }
+                // Try Filled first at the first Unknown cell
+                if let Some((row, col)) = find_unknown_cell(&grid) {
+                    decisions.push(Decision {
+                        row,
+                        col,
+                        trail_len: trail.len(),
+                    });
+                    stats.max_depth = stats.max_depth.max(decisions.len() as u32);
+                    grid[row][col] = CellState::Filled;
+                    trail.push((row, col));
+                    continue;
                 }
             }
         }
-    }
 
-    // Find the first Unknown cell
-    let (row, col) = match find_unknown_cell(&grid) {
-        Some(pos) => pos,
-        None => return,
-    };
-
-    // Try Filled first
-    let mut grid_filled = grid.clone();
-    grid_filled[row][col] = CellState::Filled;
-    backtrack_recursive(
-        grid_filled,
-        row_hints,
-        col_hints,
-        max_solutions,
-        deadline,
-        solutions,
-        timed_out,
-    );
-
-    if solutions.len() >= max_solutions || *timed_out {
-        return;
+        // Undo back to the latest decision and try Empty instead
+        let Some(decision) = decisions.pop() else {
+            return (solutions, None, stats);
+        };
+        for (row, col) in trail.drain(decision.trail_len..) {
+            grid[row][col] = CellState::Unknown;
+        }
+        grid[decision.row][decision.col] = CellState::Empty;
+        trail.push((decision.row, decision.col));
     }
+}
+
+/// Filled cells as 1, everything else as 0.
+fn grid_to_u8(grid: &Grid) -> Vec<Vec<u8>> {
+    grid.iter()
+        .map(|row| {
+            row.iter()
+                .map(|&c| (c == CellState::Filled) as u8)
+                .collect()
+        })
+        .collect()
+}
 
-    // Try Empty
-    grid[row][col] = CellState::Empty;
-    backtrack_recursive(
-        grid,
-        row_hints,
-        col_hints,
-        max_solutions,
-        deadline,
-        solutions,
-        timed_out,
-    );
+fn is_past(deadline: Option<Instant>) -> bool {
+    deadline.is_some_and(|dl| Instant::now() >= dl)
 }
 
 fn find_unknown_cell(grid: &Grid) -> Option<(usize, usize)> {
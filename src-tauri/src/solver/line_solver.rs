@@ -1,31 +1,110 @@
-use super::pattern::{filter_patterns, generate_patterns};
+use super::puzzle::normalize_hints;
 use super::types::CellState;
 
 /// Solve a single line: given current cell states and hints,
 /// return updated cell states with any newly determined cells.
 /// Returns None if the line has no valid patterns (contradiction).
+///
+/// Instead of enumerating every pattern, this runs a forward and a backward
+/// reachability pass over (cell, block) pairs, so time and memory are
+/// O(width × hints) even for long lines with many blocks.
 pub fn solve_line(line: &[CellState], hints: &[u32]) -> Option<Vec<CellState>> {
-    let effective_hints = normalize_hints(hints);
+    let hints = normalize_hints(hints);
+    let n = line.len();
+    let k = hints.len();
+    let blocks: Vec<usize> = hints.iter().map(|&h| h as usize).collect();
 
-    let all_patterns = generate_patterns(line.len(), &effective_hints);
-    let valid = filter_patterns(&all_patterns, line);
+    // empty_before[i]: number of cells known Empty in 0..i
+    let mut empty_before = vec![0usize; n + 1];
+    for (i, cell) in line.iter().enumerate() {
+        empty_before[i + 1] = empty_before[i] + usize::from(*cell == CellState::Empty);
+    }
+    // Where a block of `len` starting at `start` ends (including its separator),
+    // or None if it does not fit there.
+    let place = |start: usize, len: usize| -> Option<usize> {
+        if len > n - start || empty_before[start + len] != empty_before[start] {
+            return None;
+        }
+        let end = start + len;
+        if end == n {
+            Some(end)
+        } else if line[end] != CellState::Filled {
+            Some(end + 1)
+        } else {
+            None
+        }
+    };
 
-    if valid.is_empty() {
+    // forward[i][j]: cells 0..i can hold exactly the first j blocks
+    let mut forward = vec![vec![false; k + 1]; n + 1];
+    forward[0][0] = true;
+    for i in 0..n {
+        for j in 0..=k {
+            if !forward[i][j] {
+                continue;
+            }
+            if line[i] != CellState::Filled {
+                forward[i + 1][j] = true;
+            }
+            if j < k {
+                if let Some(next) = place(i, blocks[j]) {
+                    forward[next][j + 1] = true;
+                }
+            }
+        }
+    }
+    if !forward[n][k] {
         return None; // Contradiction: no valid pattern exists
     }
 
+    // backward[i][j]: cells i..n can hold exactly blocks j..k
+    let mut backward = vec![vec![false; k + 1]; n + 1];
+    backward[n][k] = true;
+    for i in (0..n).rev() {
+        for j in 0..=k {
+            let as_empty = line[i] != CellState::Filled && backward[i + 1][j];
+            let as_block = j < k && place(i, blocks[j]).is_some_and(|next| backward[next][j + 1]);
+            backward[i][j] = as_empty || as_block;
+        }
+    }
+
+    // Mark which values each cell takes in at least one complete pattern.
+    // Filled ranges are accumulated in a difference array.
+    let mut can_empty = vec![false; n];
+    let mut filled_delta = vec![0isize; n + 1];
+    for i in 0..n {
+        for j in 0..=k {
+            if !forward[i][j] {
+                continue;
+            }
+            if line[i] != CellState::Filled && backward[i + 1][j] {
+                can_empty[i] = true;
+            }
+            if j < k {
+                if let Some(next) = place(i, blocks[j]).filter(|&next| backward[next][j + 1]) {
+                    let end = i + blocks[j];
+                    filled_delta[i] += 1;
+                    filled_delta[end] -= 1;
+                    if next > end {
+                        can_empty[end] = true; // Separator after the block
+                    }
+                }
+            }
+        }
+    }
+
     let mut result = line.to_vec();
-    for i in 0..line.len() {
+    let mut filled_count = 0isize;
+    for i in 0..n {
+        filled_count += filled_delta[i];
         if result[i] != CellState::Unknown {
             continue; // Already determined
         }
 
-        let all_filled = valid.iter().all(|p| p[i] == CellState::Filled);
-        let all_empty = valid.iter().all(|p| p[i] == CellState::Empty);
-
-        if all_filled {
+        let can_fill = filled_count > 0;
+        if can_fill && !can_empty[i] {
             result[i] = CellState::Filled;
-        } else if all_empty {
+        } else if can_empty[i] && !can_fill {
             result[i] = CellState::Empty;
         }
     }
@@ -33,18 +112,10 @@ pub fn solve_line(line: &[CellState], hints: &[u32]) -> Option<Vec<CellState>> {
     Some(result)
 }
 
-/// Normalize hints: treat [0] as empty (same as []).
-fn normalize_hints(hints: &[u32]) -> Vec<u32> {
-    if hints.len() == 1 && hints[0] == 0 {
-        vec![]
-    } else {
-        hints.to_vec()
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::solver::pattern::solve_line_by_patterns;
 
     #[test]
     fn test_solve_line_full() {
@@ -86,4 +157,49 @@ mod tests {
         let result = solve_line(&line, &[5]);
         assert!(result.is_none());
     }
+
+    #[test]
+    fn test_solve_line_matches_patterns() {
+        // Every partial assignment of a width-7 line against a few hint sets
+        let hint_sets: [&[u32]; 6] = [&[], &[1], &[3], &[1, 1], &[2, 1, 1], &[7]];
+        for code in 0..3usize.pow(7) {
+            let line: Vec<CellState> = (0..7)
+                .map(|i| match code / 3usize.pow(i) % 3 {
+                    0 => CellState::Unknown,
+                    1 => CellState::Filled,
+                    _ => CellState::Empty,
+                })
+                .collect();
+            for hints in hint_sets {
+                assert_eq!(
+                    solve_line(&line, hints),
+                    solve_line_by_patterns(&line, hints),
+                    "line {:?}, hints {:?}",
+                    line,
+                    hints
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_solve_line_long_line_many_blocks() {
+        // Far too many patterns to enumerate: C(76, 25)
+        let line = vec![CellState::Unknown; 100];
+        let result = solve_line(&line, &[1; 25]).unwrap();
+        assert!(result.iter().all(|c| *c == CellState::Unknown));
+
+        // 50 single cells in 99 leave no slack at all
+        let result = solve_line(&line[..99], &[1; 50]).unwrap();
+        assert_eq!(result[0], CellState::Filled);
+        assert_eq!(result[1], CellState::Empty);
+        assert_eq!(result[98], CellState::Filled);
+    }
+
+    #[test]
+    fn test_solve_line_oversized_hint() {
+        let line = vec![CellState::Unknown; 5];
+        assert!(solve_line(&line, &[u32::MAX]).is_none());
+        assert!(solve_line(&line, &[3, u32::MAX]).is_none());
+    }
 }
@@ -1,17 +1,22 @@
 use std::time::Instant;
 
 use super::line_solver::solve_line;
+use super::puzzle::Puzzle;
 use super::types::{CellState, Grid};
 
 /// Apply iterative logical deduction to the grid.
 /// Returns Ok(true) if grid is fully solved, Ok(false) if unsolved cells remain,
 /// Err(()) if a contradiction is found.
+/// Every cell it determines is appended to `trail` so that the caller can undo it,
+/// and every line solved is counted in `line_solver_calls`.
 pub fn logical_solve(
     grid: &mut Grid,
-    row_hints: &[Vec<u32>],
-    col_hints: &[Vec<u32>],
+    puzzle: &Puzzle,
     deadline: Option<Instant>,
+    trail: &mut Vec<(usize, usize)>,
+    line_solver_calls: &mut u64,
 ) -> Result<bool, ()> {
+    let (row_hints, col_hints) = (puzzle.row_hints(), puzzle.col_hints());
     let height = grid.len();
     let width = if height > 0 { grid[0].len() } else { return Ok(true) };
     let mut changed = true;
@@ -27,13 +32,17 @@ pub fn logical_solve(
 
         // Process each row
         for row in 0..height {
+            *line_solver_calls += 1;
             let new_row = solve_line(&grid[row], &row_hints[row]);
             match new_row {
                 None => return Err(()),
                 Some(new) => {
-                    if new != grid[row] {
-                        grid[row] = new;
-                        changed = true;
+                    for col in 0..width {
+                        if new[col] != grid[row][col] {
+                            grid[row][col] = new[col];
+                            trail.push((row, col));
+                            changed = true;
+                        }
                     }
                 }
             }
@@ -42,15 +51,17 @@ pub fn logical_solve(
         // Process each column
         for col in 0..width {
             let col_data: Vec<CellState> = (0..height).map(|r| grid[r][col]).collect();
+            *line_solver_calls += 1;
             let new_col = solve_line(&col_data, &col_hints[col]);
             match new_col {
                 None => return Err(()),
                 Some(new) => {
-                    if new != col_data {
-                        for r in 0..height {
-                            grid[r][col] = new[r];
+                    for row in 0..height {
+                        if new[row] != col_data[row] {
+                            grid[row][col] = new[row];
+                            trail.push((row, col));
+                            changed = true;
                         }
-                        changed = true;
                     }
                 }
             }
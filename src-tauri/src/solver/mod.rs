@@ -1,6 +1,14 @@
 pub mod backtrack;
 pub mod line_solver;
-pub mod logical_solver;
+pub(crate) mod logical_solver;
+pub mod options;
+/// Exhaustive pattern enumeration, kept as a reference for testing the line solver.
+#[cfg(test)]
 pub mod pattern;
+/// Randomized differential tests against brute-force references.
+#[cfg(test)]
+mod property_tests;
+pub mod puzzle;
+pub mod rating;
 pub mod types;
 pub mod validator;
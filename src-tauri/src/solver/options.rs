@@ -0,0 +1,137 @@
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use super::types::SearchBudget;
+
+/// How a solve should run. Build it with the chained setters, or deserialize
+/// it from the frontend's camelCase JSON; missing fields take their defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SolverOptions {
+    /// Stop after this many solutions; two are enough to tell whether the
+    /// solution is unique.
+    pub max_solutions: usize,
+    /// Give up this long after the solve starts.
+    pub timeout_seconds: Option<f64>,
+    /// Give up at this moment, e.g. one shared by several solves.
+    /// Not serialized; the earlier of this and `timeout_seconds` applies.
+    #[serde(skip)]
+    pub deadline: Option<Instant>,
+    pub budget: SearchBudget,
+}
+
+impl Default for SolverOptions {
+    fn default() -> Self {
+        SolverOptions {
+            max_solutions: 2,
+            timeout_seconds: None,
+            deadline: None,
+            budget: SearchBudget::default(),
+        }
+    }
+}
+
+impl SolverOptions {
+    pub fn new() -> Self {
+        SolverOptions::default()
+    }
+
+    pub fn max_solutions(mut self, max_solutions: usize) -> Self {
+        self.max_solutions = max_solutions;
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout_seconds = Some(timeout.as_secs_f64());
+        self
+    }
+
+    pub fn deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    pub fn budget(mut self, budget: SearchBudget) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    pub fn max_nodes(mut self, max_nodes: u64) -> Self {
+        self.budget.max_nodes = Some(max_nodes);
+        self
+    }
+
+    pub fn max_line_solver_calls(mut self, max_calls: u64) -> Self {
+        self.budget.max_line_solver_calls = Some(max_calls);
+        self
+    }
+
+    /// The moment a solve starting at `started` must stop, if any.
+    /// A negative timeout has already expired; one too long to represent never does.
+    pub fn deadline_from(&self, started: Instant) -> Option<Instant> {
+        let timeout = self.timeout_seconds.and_then(|seconds| {
+            Duration::try_from_secs_f64(seconds.max(0.0))
+                .ok()
+                .and_then(|timeout| started.checked_add(timeout))
+        });
+        match (self.deadline, timeout) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_options_serde() {
+        let options = SolverOptions::new()
+            .max_solutions(5)
+            .timeout(Duration::from_millis(1500))
+            .max_nodes(1000);
+        let json = serde_json::to_value(&options).unwrap();
+        assert_eq!(json["maxSolutions"], 5);
+        assert_eq!(json["timeoutSeconds"], 1.5);
+        assert_eq!(json["budget"]["maxNodes"], 1000);
+        assert_eq!(
+            serde_json::from_value::<SolverOptions>(json).unwrap(),
+            options
+        );
+
+        let partial: SolverOptions = serde_json::from_str(r#"{"timeoutSeconds": 30}"#).unwrap();
+        assert_eq!(partial.max_solutions, 2);
+        assert_eq!(partial.budget, SearchBudget::default());
+    }
+
+    #[test]
+    fn test_deadline_from() {
+        let started = Instant::now();
+        assert_eq!(SolverOptions::new().deadline_from(started), None);
+
+        let options = SolverOptions::new().timeout(Duration::from_secs(10));
+        assert_eq!(
+            options.deadline_from(started),
+            Some(started + Duration::from_secs(10))
+        );
+        // The earlier of the two limits wins
+        let sooner = started + Duration::from_secs(1);
+        assert_eq!(
+            options.clone().deadline(sooner).deadline_from(started),
+            Some(sooner)
+        );
+
+        let expired = SolverOptions {
+            timeout_seconds: Some(-1.0),
+            ..SolverOptions::default()
+        };
+        assert_eq!(expired.deadline_from(started), Some(started));
+        let endless = SolverOptions {
+            timeout_seconds: Some(f64::INFINITY),
+            ..SolverOptions::default()
+        };
+        assert_eq!(endless.deadline_from(started), None);
+    }
+}
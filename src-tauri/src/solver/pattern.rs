@@ -1,3 +1,4 @@
+use super::puzzle::normalize_hints;
 use super::types::CellState;
 
 /// Generate all valid patterns for a line of given width with given hints.
@@ -95,6 +96,27 @@ pub fn filter_patterns(patterns: &[Vec<CellState>], known: &[CellState]) -> Vec<
         .collect()
 }
 
+/// What `solve_line` should return, worked out from every compatible pattern:
+/// a cell is kept only where all of them agree.
+pub fn solve_line_by_patterns(line: &[CellState], hints: &[u32]) -> Option<Vec<CellState>> {
+    let patterns = generate_patterns(line.len(), &normalize_hints(hints));
+    let valid = filter_patterns(&patterns, line);
+    if valid.is_empty() {
+        return None;
+    }
+    Some(
+        (0..line.len())
+            .map(|i| {
+                if valid.iter().all(|p| p[i] == valid[0][i]) {
+                    valid[0][i]
+                } else {
+                    line[i]
+                }
+            })
+            .collect(),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
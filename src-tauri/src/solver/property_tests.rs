@@ -0,0 +1,304 @@
+//! Randomized checks of the solver against brute-force references.
+//!
+//! Cases come from a fixed seed so failures reproduce; set `PROPERTY_SEED` to
+//! explore other cases and `PROPERTY_CASES` to run more of them. A failing case
+//! is shrunk to a minimal one before it is reported.
+
+use std::fmt::Debug;
+
+use super::backtrack::backtrack_solve;
+use super::line_solver::solve_line;
+use super::pattern::solve_line_by_patterns;
+use super::options::SolverOptions;
+use super::puzzle::Puzzle;
+use super::types::CellState;
+use crate::puzzle::hint_generator::generate_hints;
+
+const DEFAULT_SEED: u64 = 0x5eed_1234_abcd_0001;
+const DEFAULT_CASES: usize = 200;
+
+/// xorshift64*, enough to spread test cases around.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Rng(seed.max(1))
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// Uniform in `low..=high`.
+    fn range(&mut self, low: usize, high: usize) -> usize {
+        low + (self.next() % (high - low + 1) as u64) as usize
+    }
+
+    /// True with probability `percent` / 100.
+    fn chance(&mut self, percent: u64) -> bool {
+        self.next() % 100 < percent
+    }
+}
+
+fn env_or<T: std::str::FromStr>(name: &str, default: T) -> T {
+    std::env::var(name)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+/// Take the first smaller case that still fails, until none does.
+fn shrink<T: Clone>(mut case: T, smaller: impl Fn(&T) -> Vec<T>, fails: impl Fn(&T) -> bool) -> T {
+    'search: loop {
+        for candidate in smaller(&case) {
+            if fails(&candidate) {
+                case = candidate;
+                continue 'search;
+            }
+        }
+        return case;
+    }
+}
+
+/// Run `property` on generated cases and panic with a shrunk counterexample.
+fn check<T: Clone + Debug>(
+    generate: impl Fn(&mut Rng) -> T,
+    smaller: impl Fn(&T) -> Vec<T>,
+    property: impl Fn(&T) -> Result<(), String>,
+) {
+    let seed = env_or("PROPERTY_SEED", DEFAULT_SEED);
+    let cases = env_or("PROPERTY_CASES", DEFAULT_CASES);
+    let mut rng = Rng::new(seed);
+    for _ in 0..cases {
+        let case = generate(&mut rng);
+        if property(&case).is_ok() {
+            continue;
+        }
+        let minimal = shrink(case, &smaller, |c| property(c).is_err());
+        panic!(
+            "property failed (PROPERTY_SEED={}) for {:?}: {}",
+            seed,
+            minimal,
+            property(&minimal).unwrap_err()
+        );
+    }
+}
+
+fn random_grid(rng: &mut Rng, max_width: usize, max_height: usize) -> Vec<Vec<u8>> {
+    let width = rng.range(1, max_width);
+    let height = rng.range(1, max_height);
+    let density = rng.range(20, 80) as u64;
+    (0..height)
+        .map(|_| (0..width).map(|_| rng.chance(density) as u8).collect())
+        .collect()
+}
+
+/// Drop a row or a column, or clear a cell.
+fn smaller_grids(grid: &[Vec<u8>]) -> Vec<Vec<Vec<u8>>> {
+    let height = grid.len();
+    let width = grid[0].len();
+    let mut result = Vec::new();
+    if height > 1 {
+        for r in 0..height {
+            let mut g = grid.to_vec();
+            g.remove(r);
+            result.push(g);
+        }
+    }
+    if width > 1 {
+        for c in 0..width {
+            let mut g = grid.to_vec();
+            g.iter_mut().for_each(|row| {
+                row.remove(c);
+            });
+            result.push(g);
+        }
+    }
+    for r in 0..height {
+        for c in 0..width {
+            if grid[r][c] == 1 {
+                let mut g = grid.to_vec();
+                g[r][c] = 0;
+                result.push(g);
+            }
+        }
+    }
+    result
+}
+
+/// Every picture with these hints, by trying all 2^(width × height) grids.
+fn brute_force_solutions(
+    width: usize,
+    height: usize,
+    row_hints: &[Vec<u32>],
+    col_hints: &[Vec<u32>],
+) -> Vec<Vec<Vec<u8>>> {
+    let cells = width * height;
+    assert!(cells <= 16, "too many cells to enumerate");
+    (0u32..1 << cells)
+        .map(|bits| {
+            (0..height)
+                .map(|r| {
+                    (0..width)
+                        .map(|c| (bits >> (r * width + c) & 1) as u8)
+                        .collect()
+                })
+                .collect::<Vec<Vec<u8>>>()
+        })
+        .filter(|grid| generate_hints(grid).unwrap() == (row_hints.to_vec(), col_hints.to_vec()))
+        .collect()
+}
+
+fn solve_all(row_hints: &[Vec<u32>], col_hints: &[Vec<u32>], max: usize) -> Vec<Vec<Vec<u8>>> {
+    let puzzle = Puzzle::from_hints(row_hints.to_vec(), col_hints.to_vec()).unwrap();
+    let options = SolverOptions::new().max_solutions(max);
+    let (solutions, stopped) = backtrack_solve(&puzzle.unknown_grid(), &puzzle, &options);
+    assert_eq!(stopped, None);
+    solutions
+        .iter()
+        .map(|g| {
+            g.iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|&c| (c == CellState::Filled) as u8)
+                        .collect()
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn prop_backtrack_finds_original_picture() {
+    // Stop well short of enumerating every solution of an open 7x7 grid
+    const MAX_SOLUTIONS: usize = 64;
+    check(
+        |rng| random_grid(rng, 7, 7),
+        |g: &Vec<Vec<u8>>| smaller_grids(g),
+        |grid| {
+            let (rows, cols) = generate_hints(grid).unwrap();
+            let solutions = solve_all(&rows, &cols, MAX_SOLUTIONS);
+            for solution in &solutions {
+                if generate_hints(solution).unwrap() != (rows.clone(), cols.clone()) {
+                    return Err(format!("{:?} does not fit the hints", solution));
+                }
+            }
+            if solutions.len() < MAX_SOLUTIONS && !solutions.contains(grid) {
+                return Err(format!("original missing from {:?}", solutions));
+            }
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn prop_solution_count_matches_brute_force() {
+    check(
+        |rng| random_grid(rng, 4, 4),
+        |g: &Vec<Vec<u8>>| smaller_grids(g),
+        |grid| {
+            let (rows, cols) = generate_hints(grid).unwrap();
+            let mut expected = brute_force_solutions(grid[0].len(), grid.len(), &rows, &cols);
+            let mut found = solve_all(&rows, &cols, usize::MAX);
+            expected.sort();
+            found.sort();
+            if found != expected {
+                return Err(format!("solver found {:?}, expected {:?}", found, expected));
+            }
+            Ok(())
+        },
+    );
+}
+
+type LineCase = (Vec<u32>, Vec<CellState>);
+
+/// Hints from a random picture, so most cases are solvable, and a partial
+/// line that may or may not agree with it.
+fn random_line_case(rng: &mut Rng) -> LineCase {
+    let width = rng.range(1, 12);
+    let picture: Vec<Vec<u8>> = vec![(0..width).map(|_| rng.chance(50) as u8).collect()];
+    let mut hints = generate_hints(&picture).unwrap().0.remove(0);
+    if rng.chance(20) {
+        hints.push(rng.range(1, 3) as u32);
+    }
+    let line = (0..width)
+        .map(|_| match rng.range(0, 3) {
+            0 => CellState::Filled,
+            1 => CellState::Empty,
+            _ => CellState::Unknown,
+        })
+        .collect();
+    (hints, line)
+}
+
+/// Shorten the line, forget a cell, or drop or shrink a hint.
+fn smaller_line_cases((hints, line): &LineCase) -> Vec<LineCase> {
+    let mut result = Vec::new();
+    if line.len() > 1 {
+        result.push((hints.clone(), line[1..].to_vec()));
+        result.push((hints.clone(), line[..line.len() - 1].to_vec()));
+    }
+    for i in 0..line.len() {
+        if line[i] != CellState::Unknown {
+            let mut l = line.clone();
+            l[i] = CellState::Unknown;
+            result.push((hints.clone(), l));
+        }
+    }
+    for i in 0..hints.len() {
+        let mut h = hints.clone();
+        h.remove(i);
+        result.push((h, line.clone()));
+        if hints[i] > 1 {
+            let mut h = hints.clone();
+            h[i] -= 1;
+            result.push((h, line.clone()));
+        }
+    }
+    result
+}
+
+#[test]
+fn prop_solve_line_matches_patterns() {
+    check(random_line_case, smaller_line_cases, |(hints, line)| {
+        let fast = solve_line(line, hints);
+        let reference = solve_line_by_patterns(line, hints);
+        if fast != reference {
+            return Err(format!(
+                "solve_line gave {:?}, patterns give {:?}",
+                fast, reference
+            ));
+        }
+        Ok(())
+    });
+}
+
+#[test]
+fn test_shrink_finds_minimal_case() {
+    // "No row has three filled cells in a row" fails on any such grid; the
+    // smallest counterexample is a single row of three
+    let grid = vec![
+        vec![0, 1, 1, 1, 0],
+        vec![1, 1, 0, 1, 1],
+        vec![1, 1, 1, 1, 0],
+    ];
+    let has_run = |g: &Vec<Vec<u8>>| g.iter().any(|row| row.windows(3).any(|w| w == [1, 1, 1]));
+    assert_eq!(
+        shrink(grid, |g: &Vec<Vec<u8>>| smaller_grids(g), has_run),
+        vec![vec![1, 1, 1]]
+    );
+
+    let (hints, line) = shrink(
+        (
+            vec![3, 1],
+            vec![CellState::Filled, CellState::Empty, CellState::Unknown],
+        ),
+        smaller_line_cases,
+        |(h, l)| l.len() >= 2 && h.len() == 2,
+    );
+    assert_eq!(hints, vec![1, 1]);
+    assert_eq!(line, vec![CellState::Unknown; 2]);
+}
@@ -0,0 +1,118 @@
+use super::types::{CellState, Grid, SolverSettings, ValidationError, GRID_SIZE_LIMIT};
+use super::validator::validate_structure;
+
+/// A puzzle the solver can work on: one row hint per row, one column hint per
+/// column, every hint fitting its line, and sizes within `GRID_SIZE_LIMIT`.
+/// Hints are normalized, so an empty line is always `[]`, never `[0]`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Puzzle {
+    row_hints: Vec<Vec<u32>>,
+    col_hints: Vec<Vec<u32>>,
+}
+
+impl Puzzle {
+    /// Check the hints against the given size; the errors are the validator's.
+    pub fn new(
+        width: usize,
+        height: usize,
+        row_hints: Vec<Vec<u32>>,
+        col_hints: Vec<Vec<u32>>,
+    ) -> Result<Self, Vec<ValidationError>> {
+        let settings = SolverSettings {
+            max_grid_size: GRID_SIZE_LIMIT,
+        };
+        Puzzle::with_settings(width, height, row_hints, col_hints, &settings)
+    }
+
+    /// Like `new`, sized by the number of hints.
+    pub fn from_hints(
+        row_hints: Vec<Vec<u32>>,
+        col_hints: Vec<Vec<u32>>,
+    ) -> Result<Self, Vec<ValidationError>> {
+        Puzzle::new(col_hints.len(), row_hints.len(), row_hints, col_hints)
+    }
+
+    /// Like `new`, with the size limit of `settings`.
+    pub fn with_settings(
+        width: usize,
+        height: usize,
+        row_hints: Vec<Vec<u32>>,
+        col_hints: Vec<Vec<u32>>,
+        settings: &SolverSettings,
+    ) -> Result<Self, Vec<ValidationError>> {
+        let errors = validate_structure(width, height, &row_hints, &col_hints, settings);
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        let normalize = |lines: Vec<Vec<u32>>| -> Vec<Vec<u32>> {
+            lines.iter().map(|h| normalize_hints(h)).collect()
+        };
+        Ok(Puzzle {
+            row_hints: normalize(row_hints),
+            col_hints: normalize(col_hints),
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.col_hints.len()
+    }
+
+    pub fn height(&self) -> usize {
+        self.row_hints.len()
+    }
+
+    pub fn row_hints(&self) -> &[Vec<u32>] {
+        &self.row_hints
+    }
+
+    pub fn col_hints(&self) -> &[Vec<u32>] {
+        &self.col_hints
+    }
+
+    /// A grid of this puzzle's size with nothing determined yet.
+    pub fn unknown_grid(&self) -> Grid {
+        vec![vec![CellState::Unknown; self.width()]; self.height()]
+    }
+}
+
+/// Normalize hints: treat [0] as empty (same as []).
+pub fn normalize_hints(hints: &[u32]) -> Vec<u32> {
+    if hints.len() == 1 && hints[0] == 0 {
+        vec![]
+    } else {
+        hints.to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::types::Axis;
+
+    #[test]
+    fn test_puzzle_normalizes_hints() {
+        let puzzle = Puzzle::from_hints(vec![vec![0], vec![2]], vec![vec![1], vec![]]).unwrap();
+        assert_eq!((puzzle.width(), puzzle.height()), (2, 2));
+        assert_eq!(puzzle.row_hints(), &[vec![], vec![2]]);
+        assert_eq!(puzzle.col_hints(), &[vec![1], vec![]]);
+        assert_eq!(puzzle.unknown_grid(), vec![vec![CellState::Unknown; 2]; 2]);
+    }
+
+    #[test]
+    fn test_puzzle_rejects_inconsistent_hints() {
+        let errors = Puzzle::new(3, 2, vec![vec![1]; 2], vec![vec![1]; 2]).unwrap_err();
+        assert_eq!(
+            errors,
+            vec![ValidationError::HintCountMismatch {
+                axis: Axis::Column,
+                count: 2,
+                expected: 3,
+            }]
+        );
+        assert!(Puzzle::from_hints(vec![vec![3]], vec![vec![1]; 2]).is_err());
+        assert!(Puzzle::from_hints(vec![], vec![]).is_err());
+
+        let small = SolverSettings { max_grid_size: 1 };
+        assert!(Puzzle::with_settings(2, 1, vec![vec![1]], vec![vec![1], vec![]], &small).is_err());
+    }
+}
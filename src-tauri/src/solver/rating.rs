@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+
+use super::backtrack::solve;
+use super::options::SolverOptions;
+use super::puzzle::Puzzle;
+use super::types::SolveResult;
+
+/// How much a puzzle with a unique solution asks of the solver.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Difficulty {
+    /// Line-by-line deduction alone solves it.
+    LineLogic,
+    /// It needs guesses; `maxDepth` in the stats tells how many at once.
+    Guessing,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Rating {
+    /// `None` unless the solve found a unique solution.
+    pub difficulty: Option<Difficulty>,
+    /// The solve the rating is based on.
+    pub result: SolveResult,
+}
+
+/// Solve the puzzle and rate it by the search that took.
+pub fn rate(puzzle: &Puzzle, options: &SolverOptions) -> Rating {
+    Rating::new(solve(puzzle, options))
+}
+
+impl Rating {
+    pub fn new(result: SolveResult) -> Self {
+        let difficulty = match &result {
+            SolveResult::UniqueSolution { stats, .. } if stats.max_depth == 0 => {
+                Some(Difficulty::LineLogic)
+            }
+            SolveResult::UniqueSolution { .. } => Some(Difficulty::Guessing),
+            _ => None,
+        };
+        Rating { difficulty, result }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn difficulty(row_hints: Vec<Vec<u32>>, col_hints: Vec<Vec<u32>>) -> Option<Difficulty> {
+        let puzzle = Puzzle::from_hints(row_hints, col_hints).unwrap();
+        rate(&puzzle, &SolverOptions::new().max_solutions(1)).difficulty
+    }
+
+    #[test]
+    fn test_rate() {
+        let cross = vec![vec![1], vec![3], vec![1]];
+        assert_eq!(
+            difficulty(cross.clone(), cross),
+            Some(Difficulty::LineLogic)
+        );
+
+        // Unique, but every line leaves a choice until one cell is guessed
+        let rows = vec![vec![2], vec![1, 1], vec![2], vec![1], vec![2]];
+        let cols = vec![vec![], vec![2, 1], vec![1, 1], vec![1], vec![2, 1]];
+        assert_eq!(difficulty(rows, cols), Some(Difficulty::Guessing));
+
+        // Two solutions; a one-solution limit must not hide that
+        assert_eq!(difficulty(vec![vec![1]; 2], vec![vec![1]; 2]), None);
+
+        // Nor may a budget that stops the search after the first one
+        let puzzle = Puzzle::from_hints(vec![vec![1]; 2], vec![vec![1]; 2]).unwrap();
+        let rating = rate(&puzzle, &SolverOptions::new().max_nodes(2));
+        assert_eq!(rating.difficulty, None);
+        assert!(matches!(rating.result, SolveResult::BudgetExhausted { .. }));
+    }
+}
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -9,17 +11,211 @@ pub enum CellState {
 
 pub type Grid = Vec<Vec<CellState>>;
 
+/// Work done by one search.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchStats {
+    /// Search nodes visited, i.e. rounds of logical deduction.
+    pub nodes: u64,
+    /// Most guesses in effect at once.
+    pub max_depth: u32,
+    pub line_solver_calls: u64,
+    /// Cells deduced before the first guess.
+    pub cells_by_logic: u64,
+    /// Cells of the first solution found that needed guessing, including the
+    /// deductions that followed the guesses; 0 without a solution.
+    pub cells_by_search: u64,
+}
+
+/// A cap on search work. Unlike a deadline it is independent of machine
+/// speed, so the same puzzle stops at the same point everywhere.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SearchBudget {
+    pub max_nodes: Option<u64>,
+    /// Checked between nodes, so one node's deductions may go past it.
+    pub max_line_solver_calls: Option<u64>,
+}
+
+impl SearchBudget {
+    /// Whether the work counted in `stats` has used up the budget.
+    pub fn is_spent(&self, stats: &SearchStats) -> bool {
+        self.max_nodes.is_some_and(|max| stats.nodes >= max)
+            || self
+                .max_line_solver_calls
+                .is_some_and(|max| stats.line_solver_calls >= max)
+    }
+}
+
+/// Which limit stopped a search before it finished.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitReached {
+    Deadline,
+    Budget,
+}
+
+/// How an answer was reached, reported with every solve result.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SolveStats {
+    /// Wall-clock time of the solve, or of the validation that rejected it.
+    pub elapsed_seconds: f64,
+    pub nodes: u64,
+    pub max_depth: u32,
+    pub line_solver_calls: u64,
+    pub cells_by_logic: u64,
+    pub cells_by_search: u64,
+}
+
+impl SolveStats {
+    pub fn new(search: SearchStats, elapsed: Duration) -> Self {
+        SolveStats {
+            elapsed_seconds: elapsed.as_secs_f64(),
+            nodes: search.nodes,
+            max_depth: search.max_depth,
+            line_solver_calls: search.line_solver_calls,
+            cells_by_logic: search.cells_by_logic,
+            cells_by_search: search.cells_by_search,
+        }
+    }
+}
+
+/// `stats` defaults to zero so that results passed back from the frontend
+/// without it still deserialize.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum SolveResult {
-    UniqueSolution { grid: Vec<Vec<u8>> },
-    MultipleSolutions { grids: Vec<Vec<Vec<u8>>> },
-    NoSolution,
-    Timeout { elapsed_seconds: f64 },
+    UniqueSolution {
+        grid: Vec<Vec<u8>>,
+        #[serde(default)]
+        stats: SolveStats,
+    },
+    MultipleSolutions {
+        grids: Vec<Vec<Vec<u8>>>,
+        #[serde(default)]
+        stats: SolveStats,
+    },
+    NoSolution {
+        #[serde(default)]
+        stats: SolveStats,
+    },
+    Timeout {
+        #[serde(default)]
+        stats: SolveStats,
+    },
+    /// The search budget ran out first; unlike `Timeout` this is reproducible.
+    BudgetExhausted {
+        #[serde(default)]
+        stats: SolveStats,
+    },
+    /// The hints were rejected before solving; `messages` renders `errors`.
+    InvalidInput {
+        errors: Vec<ValidationError>,
+        messages: Vec<String>,
+        #[serde(default)]
+        stats: SolveStats,
+    },
+}
+
+impl SolveResult {
+    pub fn stats(&self) -> &SolveStats {
+        match self {
+            SolveResult::UniqueSolution { stats, .. }
+            | SolveResult::MultipleSolutions { stats, .. }
+            | SolveResult::NoSolution { stats }
+            | SolveResult::Timeout { stats }
+            | SolveResult::BudgetExhausted { stats }
+            | SolveResult::InvalidInput { stats, .. } => stats,
+        }
+    }
+}
+
+/// Hard upper bound for any configurable grid size.
+pub const GRID_SIZE_LIMIT: usize = 1000;
+
+/// Limits applied by the validator before a puzzle is solved.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SolverSettings {
+    /// Largest accepted width and height.
+    pub max_grid_size: usize,
+}
+
+impl Default for SolverSettings {
+    fn default() -> Self {
+        SolverSettings { max_grid_size: 100 }
+    }
+}
+
+/// Which family of lines a hint belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Axis {
+    Row,
+    Column,
+}
+
+/// Grid dimension checked against the allowed size range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Dimension {
+    Width,
+    Height,
+}
+
+/// A single problem found by the validator.
+/// Line indices and hint positions are 0-based; message text is rendered separately.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ValidationError {
+    DimensionOutOfRange {
+        dimension: Dimension,
+        value: usize,
+        min: usize,
+        max: usize,
+    },
+    HintCountMismatch {
+        axis: Axis,
+        count: usize,
+        expected: usize,
+    },
+    HintTooLong {
+        axis: Axis,
+        index: usize,
+        needed: u64,
+        available: usize,
+    },
+    ZeroInMultiHint {
+        axis: Axis,
+        index: usize,
+        position: usize,
+    },
+    SumMismatch {
+        row_total: u64,
+        col_total: u64,
+    },
+    /// The crossing lines leave fewer possibly-filled cells than the hints need.
+    FillCountUnreachable {
+        axis: Axis,
+        index: usize,
+        needed: u64,
+        possible: u64,
+    },
+    /// The crossing lines force more filled cells than the hints allow.
+    FillCountExceeded {
+        axis: Axis,
+        index: usize,
+        needed: u64,
+        forced: u64,
+    },
+    /// No arrangement of the hints fits the cells forced by the crossing lines.
+    LineContradiction {
+        axis: Axis,
+        index: usize,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ValidationResult {
     pub valid: bool,
-    pub errors: Vec<String>,
+    pub errors: Vec<ValidationError>,
+    /// `errors` rendered as text, in the same order.
+    pub messages: Vec<String>,
 }
@@ -1,105 +1,222 @@
-use super::types::ValidationResult;
+use super::line_solver::solve_line;
+use super::types::{
+    Axis, CellState, Dimension, Grid, SolveResult, SolveStats, SolverSettings, ValidationError,
+    ValidationResult, GRID_SIZE_LIMIT,
+};
+use crate::i18n::{Locale, Localize};
+
+const MIN_GRID_SIZE: usize = 1;
 
 pub fn validate_puzzle(
     width: usize,
     height: usize,
     row_hints: &[Vec<u32>],
     col_hints: &[Vec<u32>],
+    settings: &SolverSettings,
 ) -> ValidationResult {
-    let mut errors = Vec::new();
+    let mut errors = validate_structure(width, height, row_hints, col_hints, settings);
 
-    if width == 0 || width > 100 {
-        errors.push(format!(
-            "幅は1以上100以下である必要があります（現在: {}）",
-            width
-        ));
+    // Cross-check: total filled cells from rows vs columns should match
+    let row_total: u64 = row_hints.iter().map(|h| hint_total(h)).sum();
+    let col_total: u64 = col_hints.iter().map(|h| hint_total(h)).sum();
+    if row_total != col_total {
+        errors.push(ValidationError::SumMismatch {
+            row_total,
+            col_total,
+        });
     }
-    if height == 0 || height > 100 {
-        errors.push(format!(
-            "高さは1以上100以下である必要があります（現在: {}）",
-            height
-        ));
+
+    // Line propagation needs every hint to fit its line; a sum mismatch alone is fine
+    let structurally_sound = errors
+        .iter()
+        .all(|e| matches!(e, ValidationError::SumMismatch { .. }));
+    if structurally_sound {
+        check_line_propagation(width, height, row_hints, col_hints, &mut errors);
     }
 
+    ValidationResult {
+        valid: errors.is_empty(),
+        messages: Vec::new(),
+        errors,
+    }
+    .localized(Locale::default())
+}
+
+/// Check only the size, before anything sized by it is read or built.
+/// Sizes never exceed `GRID_SIZE_LIMIT`, whatever `settings` asks for.
+pub fn validate_dimensions(
+    width: usize,
+    height: usize,
+    settings: &SolverSettings,
+) -> Vec<ValidationError> {
+    let max = settings.max_grid_size.min(GRID_SIZE_LIMIT);
+    [(Dimension::Width, width), (Dimension::Height, height)]
+        .into_iter()
+        .filter(|&(_, value)| !(MIN_GRID_SIZE..=max).contains(&value))
+        .map(|(dimension, value)| ValidationError::DimensionOutOfRange {
+            dimension,
+            value,
+            min: MIN_GRID_SIZE,
+            max,
+        })
+        .collect()
+}
+
+/// Check only that the dimensions and hints are well-formed: sizes in range,
+/// one hint list per line, and every hint fitting its line.
+/// Says nothing about whether the puzzle can be solved.
+/// Sizes never exceed `GRID_SIZE_LIMIT`, whatever `settings` asks for.
+pub fn validate_structure(
+    width: usize,
+    height: usize,
+    row_hints: &[Vec<u32>],
+    col_hints: &[Vec<u32>],
+    settings: &SolverSettings,
+) -> Vec<ValidationError> {
+    let mut errors = validate_dimensions(width, height, settings);
+
     if row_hints.len() != height {
-        errors.push(format!(
-            "行ヒントの数（{}）が高さ（{}）と一致しません",
-            row_hints.len(),
-            height
-        ));
+        errors.push(ValidationError::HintCountMismatch {
+            axis: Axis::Row,
+            count: row_hints.len(),
+            expected: height,
+        });
     }
     if col_hints.len() != width {
-        errors.push(format!(
-            "列ヒントの数（{}）が幅（{}）と一致しません",
-            col_hints.len(),
-            width
-        ));
+        errors.push(ValidationError::HintCountMismatch {
+            axis: Axis::Column,
+            count: col_hints.len(),
+            expected: width,
+        });
     }
 
-    // Check each row hint fits within width
-    for (i, hints) in row_hints.iter().enumerate() {
+    // Each row hint must fit within the width, each column hint within the height
+    check_line_hints(Axis::Row, row_hints, width, &mut errors);
+    check_line_hints(Axis::Column, col_hints, height, &mut errors);
+
+    errors
+}
+
+/// Sum of a line's hints, widened so that absurd values cannot overflow.
+fn hint_total(hints: &[u32]) -> u64 {
+    hints.iter().map(|&h| h as u64).sum()
+}
+
+fn check_line_hints(
+    axis: Axis,
+    lines: &[Vec<u32>],
+    available: usize,
+    errors: &mut Vec<ValidationError>,
+) {
+    for (index, hints) in lines.iter().enumerate() {
         if !hints.is_empty() {
-            let min_length: u32 =
-                hints.iter().sum::<u32>() + hints.len().saturating_sub(1) as u32;
-            if min_length > width as u32 {
-                errors.push(format!(
-                    "行{}のヒント{:?}は最低{}マス必要ですが、幅は{}です",
-                    i + 1,
-                    hints,
-                    min_length,
-                    width
-                ));
+            let needed = hint_total(hints) + hints.len().saturating_sub(1) as u64;
+            if needed > available as u64 {
+                errors.push(ValidationError::HintTooLong {
+                    axis,
+                    index,
+                    needed,
+                    available,
+                });
             }
         }
-        for (j, &h) in hints.iter().enumerate() {
+        for (position, &h) in hints.iter().enumerate() {
             if h == 0 && hints.len() > 1 {
-                errors.push(format!(
-                    "行{}のヒント位置{}に0が含まれています",
-                    i + 1,
-                    j + 1
-                ));
+                errors.push(ValidationError::ZeroInMultiHint {
+                    axis,
+                    index,
+                    position,
+                });
             }
         }
     }
+}
 
-    // Check each column hint fits within height
-    for (i, hints) in col_hints.iter().enumerate() {
-        if !hints.is_empty() {
-            let min_length: u32 =
-                hints.iter().sum::<u32>() + hints.len().saturating_sub(1) as u32;
-            if min_length > height as u32 {
-                errors.push(format!(
-                    "列{}のヒント{:?}は最低{}マス必要ですが、高さは{}です",
-                    i + 1,
-                    hints,
-                    min_length,
-                    height
-                ));
-            }
+/// Run one round of line propagation and report lines that are already contradictory.
+///
+/// Every row and every column is first solved on its own. Each line is then checked
+/// against the cells its crossing lines force: first whether its fill count can still
+/// be met, then whether any arrangement of its hints fits at all.
+/// Assumes dimensions and hint counts have already been validated.
+fn check_line_propagation(
+    width: usize,
+    height: usize,
+    row_hints: &[Vec<u32>],
+    col_hints: &[Vec<u32>],
+    errors: &mut Vec<ValidationError>,
+) {
+    let mut from_rows: Grid = vec![vec![CellState::Unknown; width]; height];
+    for (row, hints) in row_hints.iter().enumerate() {
+        if let Some(line) = solve_line(&from_rows[row], hints) {
+            from_rows[row] = line;
         }
-        for (j, &h) in hints.iter().enumerate() {
-            if h == 0 && hints.len() > 1 {
-                errors.push(format!(
-                    "列{}のヒント位置{}に0が含まれています",
-                    i + 1,
-                    j + 1
-                ));
+    }
+
+    let mut from_cols: Grid = vec![vec![CellState::Unknown; width]; height];
+    for (col, hints) in col_hints.iter().enumerate() {
+        let unknown = vec![CellState::Unknown; height];
+        if let Some(line) = solve_line(&unknown, hints) {
+            for (r, cell) in line.into_iter().enumerate() {
+                from_cols[r][col] = cell;
             }
         }
     }
 
-    // Cross-check: total filled cells from rows vs columns should match
-    let row_total: u32 = row_hints.iter().flat_map(|h| h.iter()).sum();
-    let col_total: u32 = col_hints.iter().flat_map(|h| h.iter()).sum();
-    if row_total != col_total {
-        errors.push(format!(
-            "行ヒントの合計（{}）と列ヒントの合計（{}）が一致しません",
-            row_total, col_total
-        ));
+    for (row, hints) in row_hints.iter().enumerate() {
+        check_crossed_line(Axis::Row, row, &from_cols[row], hints, errors);
+    }
+    for (col, hints) in col_hints.iter().enumerate() {
+        let crossed: Vec<CellState> = from_rows.iter().map(|r| r[col]).collect();
+        check_crossed_line(Axis::Column, col, &crossed, hints, errors);
     }
+}
 
-    ValidationResult {
-        valid: errors.is_empty(),
-        errors,
+fn check_crossed_line(
+    axis: Axis,
+    index: usize,
+    crossed: &[CellState],
+    hints: &[u32],
+    errors: &mut Vec<ValidationError>,
+) {
+    let needed = hint_total(hints);
+    let forced = crossed.iter().filter(|c| **c == CellState::Filled).count() as u64;
+    let possible = crossed.iter().filter(|c| **c != CellState::Empty).count() as u64;
+
+    if possible < needed {
+        errors.push(ValidationError::FillCountUnreachable {
+            axis,
+            index,
+            needed,
+            possible,
+        });
+    } else if forced > needed {
+        errors.push(ValidationError::FillCountExceeded {
+            axis,
+            index,
+            needed,
+            forced,
+        });
+    } else if solve_line(crossed, hints).is_none() {
+        errors.push(ValidationError::LineContradiction { axis, index });
+    }
+}
+
+impl ValidationResult {
+    /// Re-render `messages` from `errors` in the given locale.
+    pub fn localized(mut self, locale: Locale) -> Self {
+        self.messages = self.errors.iter().map(|e| e.localize(locale)).collect();
+        self
+    }
+}
+
+impl SolveResult {
+    /// The result for hints the validator rejected, with messages in `locale`.
+    pub fn invalid_input(errors: Vec<ValidationError>, locale: Locale, stats: SolveStats) -> Self {
+        let messages = errors.iter().map(|e| e.localize(locale)).collect();
+        SolveResult::InvalidInput {
+            errors,
+            messages,
+            stats,
+        }
     }
 }
@@ -0,0 +1,309 @@
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::sync::{Mutex, PoisonError};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::puzzle::fingerprint::exact_hash;
+
+pub const STATS_FILE_NAME: &str = "stats.jsonl";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SolveKind {
+    /// The solver ran on the puzzle.
+    Auto,
+    /// A person played the puzzle.
+    Play,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SolveOutcome {
+    UniqueSolution,
+    MultipleSolutions,
+    NoSolution,
+    Timeout,
+    /// The solver's search budget ran out.
+    BudgetExhausted,
+    /// A play session that ended with the picture complete.
+    Solved,
+    /// A play session that ended without finishing.
+    Abandoned,
+}
+
+impl SolveOutcome {
+    fn is_success(self) -> bool {
+        matches!(self, SolveOutcome::UniqueSolution | SolveOutcome::Solved)
+    }
+}
+
+/// One line of the statistics log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SolveRecord {
+    /// Exact fingerprint of the hints.
+    pub puzzle_hash: String,
+    pub width: usize,
+    pub height: usize,
+    #[serde(default)]
+    pub difficulty: Option<String>,
+    pub kind: SolveKind,
+    pub outcome: SolveOutcome,
+    pub elapsed_ms: u64,
+    /// Search nodes the solver visited; zero for play sessions.
+    #[serde(default)]
+    pub nodes: u64,
+    /// Seconds since the Unix epoch.
+    pub date: u64,
+}
+
+impl SolveRecord {
+    /// A record dated now.
+    pub fn new(
+        row_hints: &[Vec<u32>],
+        col_hints: &[Vec<u32>],
+        difficulty: Option<String>,
+        kind: SolveKind,
+        outcome: SolveOutcome,
+        elapsed_ms: u64,
+        nodes: u64,
+    ) -> Self {
+        SolveRecord {
+            puzzle_hash: exact_hash(row_hints, col_hints),
+            width: col_hints.len(),
+            height: row_hints.len(),
+            difficulty,
+            kind,
+            outcome,
+            elapsed_ms,
+            nodes,
+            date: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GroupBy {
+    Size,
+    Difficulty,
+}
+
+/// Totals for one size (`"10x10"`) or difficulty (`"unknown"` when unset).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsGroup {
+    pub key: String,
+    pub kind: SolveKind,
+    pub count: usize,
+    /// Unique solutions found, or pictures completed.
+    pub successes: usize,
+    /// Mean elapsed time over successes.
+    pub average_ms: Option<f64>,
+    pub total_nodes: u64,
+}
+
+/// Group records by size or difficulty, keeping solver runs and play sessions apart.
+pub fn aggregate(records: &[SolveRecord], group_by: GroupBy) -> Vec<StatsGroup> {
+    let mut groups: BTreeMap<(String, u8), (SolveKind, Vec<&SolveRecord>)> = BTreeMap::new();
+    for record in records {
+        let key = match group_by {
+            GroupBy::Size => format!("{}x{}", record.width, record.height),
+            GroupBy::Difficulty => record
+                .difficulty
+                .clone()
+                .unwrap_or_else(|| "unknown".to_string()),
+        };
+        groups
+            .entry((key, record.kind as u8))
+            .or_insert_with(|| (record.kind, Vec::new()))
+            .1
+            .push(record);
+    }
+
+    groups
+        .into_iter()
+        .map(|((key, _), (kind, records))| {
+            let successes: Vec<u64> = records
+                .iter()
+                .filter(|r| r.outcome.is_success())
+                .map(|r| r.elapsed_ms)
+                .collect();
+            StatsGroup {
+                key,
+                kind,
+                count: records.len(),
+                successes: successes.len(),
+                average_ms: (!successes.is_empty())
+                    .then(|| successes.iter().sum::<u64>() as f64 / successes.len() as f64),
+                total_nodes: records.iter().map(|r| r.nodes).sum(),
+            }
+        })
+        .collect()
+}
+
+/// Fastest successful solve of a puzzle of the given kind, in milliseconds.
+pub fn best_time(records: &[SolveRecord], puzzle_hash: &str, kind: SolveKind) -> Option<u64> {
+    records
+        .iter()
+        .filter(|r| r.puzzle_hash == puzzle_hash && r.kind == kind && r.outcome.is_success())
+        .map(|r| r.elapsed_ms)
+        .min()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StatsError {
+    Read(String),
+    Serialize(String),
+    Write(String),
+}
+
+/// The append-only statistics log, one JSON record per line.
+pub struct StatsLog {
+    path: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl StatsLog {
+    pub fn new(path: PathBuf) -> Self {
+        StatsLog {
+            path,
+            lock: Mutex::new(()),
+        }
+    }
+
+    pub fn append(&self, record: &SolveRecord) -> Result<(), StatsError> {
+        let mut line =
+            serde_json::to_string(record).map_err(|e| StatsError::Serialize(e.to_string()))?;
+        line.push('\n');
+        let _guard = self.lock.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(|e| StatsError::Write(e.to_string()))?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| StatsError::Write(e.to_string()))?;
+        // Keep a line torn by an earlier crash from swallowing this record
+        if !ends_with_newline(&mut file).map_err(|e| StatsError::Write(e.to_string()))? {
+            line.insert(0, '\n');
+        }
+        file.write_all(line.as_bytes())
+            .map_err(|e| StatsError::Write(e.to_string()))
+    }
+
+    /// Every record in the log. Lines that cannot be parsed, such as one cut
+    /// short by a crash, are skipped.
+    pub fn records(&self) -> Result<Vec<SolveRecord>, StatsError> {
+        let _guard = self.lock.lock().unwrap_or_else(PoisonError::into_inner);
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let content =
+            fs::read_to_string(&self.path).map_err(|e| StatsError::Read(e.to_string()))?;
+        Ok(content
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+}
+
+/// True for an empty file too, as nothing needs separating.
+fn ends_with_newline(file: &mut File) -> io::Result<bool> {
+    if file.metadata()?.len() == 0 {
+        return Ok(true);
+    }
+    let mut last = [0u8];
+    file.seek(SeekFrom::End(-1))?;
+    file.read_exact(&mut last)?;
+    Ok(last[0] == b'\n')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_log(name: &str) -> StatsLog {
+        StatsLog::new(std::env::temp_dir().join(format!(
+            "illust-logic-stats-{}-{}.jsonl",
+            std::process::id(),
+            name
+        )))
+    }
+
+    fn record(size: usize, kind: SolveKind, outcome: SolveOutcome, elapsed_ms: u64) -> SolveRecord {
+        let hints = vec![vec![size as u32]; size];
+        SolveRecord::new(
+            &hints,
+            &hints,
+            Some("easy".to_string()),
+            kind,
+            outcome,
+            elapsed_ms,
+            0,
+        )
+    }
+
+    #[test]
+    fn test_log_roundtrip_skips_broken_lines() {
+        let log = temp_log("roundtrip");
+        let first = record(5, SolveKind::Auto, SolveOutcome::UniqueSolution, 12);
+        log.append(&first).unwrap();
+        // A torn write
+        OpenOptions::new()
+            .append(true)
+            .open(&log.path)
+            .unwrap()
+            .write_all(br#"{"puzzleHash":"#)
+            .unwrap();
+        let second = record(5, SolveKind::Play, SolveOutcome::Solved, 34);
+        log.append(&second).unwrap();
+        assert_eq!(log.records().unwrap(), vec![first, second]);
+        fs::remove_file(&log.path).unwrap();
+    }
+
+    #[test]
+    fn test_aggregate_and_best_time() {
+        let records = vec![
+            record(5, SolveKind::Play, SolveOutcome::Solved, 60_000),
+            record(5, SolveKind::Play, SolveOutcome::Solved, 40_000),
+            record(5, SolveKind::Play, SolveOutcome::Abandoned, 5_000),
+            record(10, SolveKind::Auto, SolveOutcome::Timeout, 1_000),
+        ];
+        let by_size = aggregate(&records, GroupBy::Size);
+        assert_eq!(
+            by_size,
+            vec![
+                StatsGroup {
+                    key: "10x10".to_string(),
+                    kind: SolveKind::Auto,
+                    count: 1,
+                    successes: 0,
+                    average_ms: None,
+                    total_nodes: 0,
+                },
+                StatsGroup {
+                    key: "5x5".to_string(),
+                    kind: SolveKind::Play,
+                    count: 3,
+                    successes: 2,
+                    average_ms: Some(50_000.0),
+                    total_nodes: 0,
+                },
+            ]
+        );
+        assert_eq!(aggregate(&records, GroupBy::Difficulty).len(), 2);
+
+        let hash = &records[0].puzzle_hash;
+        assert_eq!(best_time(&records, hash, SolveKind::Play), Some(40_000));
+        assert_eq!(best_time(&records, hash, SolveKind::Auto), None);
+    }
+}
@@ -0,0 +1,20 @@
+//! Panics turned into errors where callers cannot handle them: Tauri
+//! commands and functions called from C.
+
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+
+/// Run `body`, returning the panic message instead if it panics.
+pub(crate) fn catch<T>(body: impl FnOnce() -> T) -> Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(body)).map_err(|payload| panic_message(&*payload))
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
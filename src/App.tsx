@@ -1,3 +1,4 @@
+import { useEffect } from "react";
 import { usePuzzleStore } from "./store/puzzleStore";
 import { TabBar } from "./components/layout/TabBar";
 import { PuzzleTab } from "./components/puzzle/PuzzleTab";
@@ -5,6 +6,11 @@ import { SettingsTab } from "./components/settings/SettingsTab";
 
 function App() {
   const activeTab = usePuzzleStore((s) => s.activeTab);
+  const loadSettings = usePuzzleStore((s) => s.loadSettings);
+
+  useEffect(() => {
+    loadSettings();
+  }, [loadSettings]);
 
   return (
     <div className="min-h-screen bg-gray-50 flex flex-col">
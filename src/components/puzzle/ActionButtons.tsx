@@ -25,7 +25,7 @@ export function ActionButtons() {
 
   const handleSaveFile = async () => {
     const puzzleData: PuzzleData = {
-      version: "1.0",
+      version: "1.1",
       width: store.width,
       height: store.height,
       rowHints: store.rowHints,
@@ -35,7 +35,17 @@ export function ActionButtons() {
       },
     };
     try {
-      await savePuzzleDialog(puzzleData);
+      // Store the drawn picture when it is what the hints describe
+      const grid = store.grid.map((r) => r.map(Number));
+      const [rowHints, colHints] = await api.generateHints(grid);
+      const matches =
+        JSON.stringify([rowHints, colHints]) ===
+        JSON.stringify([store.rowHints, store.colHints]);
+      const hasPicture = grid.some((r) => r.some((c) => c === 1));
+      await savePuzzleDialog(
+        puzzleData,
+        matches && hasPicture ? grid : undefined
+      );
     } catch (err) {
       console.error("Save error:", err);
     }
@@ -44,17 +54,13 @@ export function ActionButtons() {
   const handleSolve = async () => {
     store.setIsSolving(true);
     store.setSolveResult(null);
-    store.setSolveTime(null);
-    const start = performance.now();
     try {
       const result = await api.solvePuzzle(
         store.rowHints,
         store.colHints,
         store.settings.timeoutSeconds
       );
-      const elapsed = (performance.now() - start) / 1000;
       store.setSolveResult(result);
-      store.setSolveTime(elapsed);
     } catch (err) {
       console.error("Solve error:", err);
     } finally {
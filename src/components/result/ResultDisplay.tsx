@@ -1,15 +1,12 @@
 import { usePuzzleStore } from "../../store/puzzleStore";
 import { SolutionGrid } from "./SolutionGrid";
+import { SolveStatsList } from "./SolveStatsList";
 
 export function ResultDisplay() {
   const solveResult = usePuzzleStore((s) => s.solveResult);
-  const solveTime = usePuzzleStore((s) => s.solveTime);
 
   if (!solveResult) return null;
 
-  const timeText =
-    solveTime !== null ? `計算時間: ${solveTime.toFixed(2)}秒` : "";
-
   switch (solveResult.type) {
     case "UniqueSolution":
       return (
@@ -23,9 +20,10 @@ export function ResultDisplay() {
           <div className="mb-3 overflow-auto">
             <SolutionGrid grid={solveResult.grid} />
           </div>
-          {timeText && (
-            <p className="text-sm text-green-700">{timeText}</p>
-          )}
+          <SolveStatsList
+            stats={solveResult.stats}
+            className="text-green-700"
+          />
         </div>
       );
 
@@ -53,9 +51,10 @@ export function ResultDisplay() {
               </div>
             ))}
           </div>
-          {timeText && (
-            <p className="text-sm text-amber-700">{timeText}</p>
-          )}
+          <SolveStatsList
+            stats={solveResult.stats}
+            className="text-amber-700"
+          />
         </div>
       );
 
@@ -71,9 +70,10 @@ export function ResultDisplay() {
           <p className="text-sm text-red-700 mb-2">
             ヒントに矛盾があるため、解答不可能な問題です。
           </p>
-          {timeText && (
-            <p className="text-sm text-red-700">{timeText}</p>
-          )}
+          <SolveStatsList
+            stats={solveResult.stats}
+            className="text-red-700"
+          />
         </div>
       );
 
@@ -96,6 +96,46 @@ export function ResultDisplay() {
               <li>設定でタイムアウト時間を延長する</li>
             </ul>
           </div>
+          <SolveStatsList
+            stats={solveResult.stats}
+            className="mt-2 text-gray-600"
+          />
+        </div>
+      );
+
+    case "BudgetExhausted":
+      return (
+        <div className="mt-2 p-3 bg-gray-50 border border-gray-200 rounded-lg">
+          <div className="flex items-center gap-2 mb-2">
+            <span className="text-gray-600 text-lg font-bold">&#9201;</span>
+            <h3 className="text-gray-800 font-medium">
+              探索の上限に達しました
+            </h3>
+          </div>
+          <p className="text-sm text-gray-700 mb-2">
+            指定された探索ノード数または解析回数の範囲内で解答できませんでした。
+          </p>
+          <SolveStatsList
+            stats={solveResult.stats}
+            className="text-gray-600"
+          />
+        </div>
+      );
+
+    case "InvalidInput":
+      return (
+        <div className="mt-2 p-3 bg-red-50 border border-red-200 rounded-lg">
+          <div className="flex items-center gap-2 mb-2">
+            <span className="text-red-600 text-lg font-bold">&#10007;</span>
+            <h3 className="text-red-800 font-medium">
+              入力が正しくありません
+            </h3>
+          </div>
+          <ul className="list-disc list-inside text-sm text-red-700">
+            {solveResult.messages.map((message, i) => (
+              <li key={i}>{message}</li>
+            ))}
+          </ul>
         </div>
       );
   }
@@ -0,0 +1,28 @@
+import type { SolveStats } from "../../types/puzzle";
+
+interface SolveStatsListProps {
+  stats: SolveStats;
+  className: string;
+}
+
+export function SolveStatsList({ stats, className }: SolveStatsListProps) {
+  const rows: [string, string][] = [
+    ["計算時間", `${stats.elapsedSeconds.toFixed(2)}秒`],
+    ["探索ノード数", stats.nodes.toLocaleString()],
+    ["最大探索深さ", stats.maxDepth.toLocaleString()],
+    ["行・列の解析回数", stats.lineSolverCalls.toLocaleString()],
+    ["論理で確定したマス", stats.cellsByLogic.toLocaleString()],
+    ["探索で確定したマス", stats.cellsBySearch.toLocaleString()],
+  ];
+
+  return (
+    <dl className={`grid grid-cols-[auto_1fr] gap-x-4 text-sm ${className}`}>
+      {rows.map(([label, value]) => (
+        <div key={label} className="contents">
+          <dt>{label}</dt>
+          <dd className="tabular-nums">{value}</dd>
+        </div>
+      ))}
+    </dl>
+  );
+}
@@ -14,7 +14,8 @@ export async function openPuzzleDialog(): Promise<PuzzleData | null> {
 }
 
 export async function savePuzzleDialog(
-  puzzleData: PuzzleData
+  puzzleData: PuzzleData,
+  solution?: number[][]
 ): Promise<boolean> {
   const now = new Date();
   const pad = (n: number) => String(n).padStart(2, "0");
@@ -26,6 +27,6 @@ export async function savePuzzleDialog(
   });
 
   if (!filePath) return false;
-  await savePuzzleFile(filePath, puzzleData);
+  await savePuzzleFile(filePath, puzzleData, solution);
   return true;
 }
@@ -1,19 +1,56 @@
 import { invoke } from "@tauri-apps/api/core";
 import type {
   SolveResult,
+  SearchBudget,
+  SolverOptions,
   ValidationResult,
   PuzzleData,
+  AppSettings,
+  SolutionCheck,
+  Transform,
+  LibraryEntry,
+  LibraryPage,
+  AddedPuzzle,
+  Duplicate,
+  ImportReport,
+  LibraryQuery,
+  SolveStatus,
+  SavedGame,
+  GameSummary,
+  MoveCheck,
+  PlayerCell,
+  SolveKind,
+  StatsGroup,
+  StatsGroupBy,
 } from "../types/puzzle";
 
 export async function solvePuzzle(
   rowHints: number[][],
   colHints: number[][],
-  timeoutSeconds?: number
+  timeoutSeconds?: number,
+  difficulty?: string,
+  budget?: SearchBudget
 ): Promise<SolveResult> {
   return await invoke<SolveResult>("solve_puzzle", {
     rowHints,
     colHints,
     timeoutSeconds: timeoutSeconds ?? null,
+    budget: budget ?? null,
+    difficulty: difficulty ?? null,
+  });
+}
+
+export async function solve(
+  rowHints: number[][],
+  colHints: number[][],
+  options?: SolverOptions,
+  difficulty?: string
+): Promise<SolveResult> {
+  return await invoke<SolveResult>("solve_command", {
+    rowHints,
+    colHints,
+    options: options ?? null,
+    difficulty: difficulty ?? null,
   });
 }
 
@@ -45,7 +82,201 @@ export async function loadPuzzleFile(path: string): Promise<PuzzleData> {
 
 export async function savePuzzleFile(
   path: string,
+  puzzleData: PuzzleData,
+  solution?: number[][]
+): Promise<void> {
+  return await invoke<void>("save_puzzle_file_command", {
+    path,
+    puzzleData,
+    solution: solution ?? null,
+  });
+}
+
+export async function checkSolution(
+  puzzleData: PuzzleData,
+  result: SolveResult
+): Promise<SolutionCheck> {
+  return await invoke<SolutionCheck>("check_solution_command", {
+    puzzleData,
+    result,
+  });
+}
+
+export async function transformPuzzle(
+  puzzleData: PuzzleData,
+  transform: Transform
+): Promise<PuzzleData> {
+  return await invoke<PuzzleData>("transform_puzzle_command", {
+    puzzleData,
+    transform,
+  });
+}
+
+export async function transformGrid(
+  grid: number[][],
+  transform: Transform
+): Promise<number[][]> {
+  return await invoke<number[][]>("transform_grid_command", {
+    grid,
+    transform,
+  });
+}
+
+export async function transformSolveResult(
+  result: SolveResult,
+  transform: Transform
+): Promise<SolveResult> {
+  return await invoke<SolveResult>("transform_solve_result_command", {
+    result,
+    transform,
+  });
+}
+
+export async function getSettings(): Promise<AppSettings> {
+  return await invoke<AppSettings>("get_settings_command");
+}
+
+export async function updateSettings(
+  newSettings: AppSettings
+): Promise<AppSettings> {
+  return await invoke<AppSettings>("update_settings_command", { newSettings });
+}
+
+export async function resetSettings(): Promise<AppSettings> {
+  return await invoke<AppSettings>("reset_settings_command");
+}
+
+export async function searchLibrary(query?: LibraryQuery): Promise<LibraryPage> {
+  return await invoke<LibraryPage>("library_search_command", {
+    query: query ?? null,
+  });
+}
+
+export async function getLibraryTags(): Promise<string[]> {
+  return await invoke<string[]>("library_tags_command");
+}
+
+export async function loadLibraryPuzzle(id: string): Promise<PuzzleData> {
+  return await invoke<PuzzleData>("library_load_command", { id });
+}
+
+export async function addToLibrary(
+  puzzleData: PuzzleData,
+  solution?: number[][],
+  tags?: string[]
+): Promise<AddedPuzzle> {
+  return await invoke<AddedPuzzle>("library_add_command", {
+    puzzleData,
+    solution: solution ?? null,
+    tags: tags ?? null,
+  });
+}
+
+export async function findLibraryDuplicates(
   puzzleData: PuzzleData
+): Promise<Duplicate[]> {
+  return await invoke<Duplicate[]>("library_duplicates_command", {
+    puzzleData,
+  });
+}
+
+export async function importToLibrary(
+  paths: string[],
+  skipDuplicates: boolean,
+  tags?: string[]
+): Promise<ImportReport> {
+  return await invoke<ImportReport>("library_import_command", {
+    paths,
+    tags: tags ?? null,
+    skipDuplicates,
+  });
+}
+
+export async function setLibraryStatus(
+  id: string,
+  status: SolveStatus
+): Promise<LibraryEntry> {
+  return await invoke<LibraryEntry>("library_set_status_command", {
+    id,
+    status,
+  });
+}
+
+export async function setLibraryTags(
+  id: string,
+  tags: string[]
+): Promise<LibraryEntry> {
+  return await invoke<LibraryEntry>("library_set_tags_command", { id, tags });
+}
+
+export async function removeFromLibrary(id: string): Promise<void> {
+  return await invoke<void>("library_remove_command", { id });
+}
+
+export async function saveGame(
+  game: SavedGame,
+  id?: string
+): Promise<GameSummary> {
+  return await invoke<GameSummary>("save_game_command", {
+    game,
+    id: id ?? null,
+  });
+}
+
+export async function autosaveGame(game: SavedGame): Promise<GameSummary> {
+  return await invoke<GameSummary>("autosave_game_command", { game });
+}
+
+export async function listGames(): Promise<GameSummary[]> {
+  return await invoke<GameSummary[]>("list_games_command");
+}
+
+export async function loadGame(id: string): Promise<SavedGame> {
+  return await invoke<SavedGame>("load_game_command", { id });
+}
+
+export async function deleteGame(id: string): Promise<void> {
+  return await invoke<void>("delete_game_command", { id });
+}
+
+export async function checkMoves(
+  rowHints: number[][],
+  colHints: number[][],
+  grid: PlayerCell[][]
+): Promise<MoveCheck> {
+  return await invoke<MoveCheck>("check_moves_command", {
+    rowHints,
+    colHints,
+    grid,
+  });
+}
+
+export async function recordPlay(
+  puzzleData: PuzzleData,
+  solved: boolean,
+  elapsedMs: number
 ): Promise<void> {
-  return await invoke<void>("save_puzzle_file_command", { path, puzzleData });
+  return await invoke<void>("record_play_command", {
+    puzzleData,
+    solved,
+    elapsedMs,
+  });
+}
+
+export async function getStatsSummary(
+  groupBy: StatsGroupBy
+): Promise<StatsGroup[]> {
+  return await invoke<StatsGroup[]>("stats_summary_command", { groupBy });
+}
+
+export async function getBestTime(
+  rowHints: number[][],
+  colHints: number[][],
+  kind?: SolveKind
+): Promise<number | null> {
+  return await invoke<number | null>("best_time_command", {
+    rowHints,
+    colHints,
+    kind: kind ?? null,
+  });
 }
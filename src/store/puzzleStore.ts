@@ -1,4 +1,5 @@
 import { create } from "zustand";
+import * as api from "../lib/tauriApi";
 import type {
   CellValue,
   SolveResult,
@@ -13,7 +14,6 @@ interface PuzzleState {
   colHints: number[][];
   isSolving: boolean;
   solveResult: SolveResult | null;
-  solveTime: number | null;
   settings: AppSettings;
   activeTab: "puzzle" | "settings";
 
@@ -27,8 +27,8 @@ interface PuzzleState {
   clearGrid: () => void;
   setIsSolving: (v: boolean) => void;
   setSolveResult: (r: SolveResult | null) => void;
-  setSolveTime: (t: number | null) => void;
   setActiveTab: (tab: "puzzle" | "settings") => void;
+  loadSettings: () => Promise<void>;
   updateSettings: (partial: Partial<AppSettings>) => void;
   resetSettings: () => void;
   loadPuzzle: (
@@ -42,11 +42,13 @@ interface PuzzleState {
 const DEFAULT_SIZE = 10;
 
 const defaultSettings: AppSettings = {
+  version: 1,
   timeoutSeconds: 60,
   maxGridSize: 100,
   showSizeWarning: true,
   gridLineThickness: "normal",
   cellSize: "medium",
+  locale: "ja",
 };
 
 function createEmptyGrid(w: number, h: number): CellValue[][] {
@@ -65,7 +67,6 @@ export const usePuzzleStore = create<PuzzleState>((set) => ({
   colHints: createEmptyHints(DEFAULT_SIZE),
   isSolving: false,
   solveResult: null,
-  solveTime: null,
   settings: { ...defaultSettings },
   activeTab: "puzzle",
 
@@ -98,7 +99,6 @@ export const usePuzzleStore = create<PuzzleState>((set) => ({
       rowHints: createEmptyHints(h),
       colHints: createEmptyHints(w),
       solveResult: null,
-      solveTime: null,
     }),
   clearGrid: () =>
     set((state) => ({
@@ -106,17 +106,24 @@ export const usePuzzleStore = create<PuzzleState>((set) => ({
       rowHints: createEmptyHints(state.height),
       colHints: createEmptyHints(state.width),
       solveResult: null,
-      solveTime: null,
     })),
   setIsSolving: (v) => set({ isSolving: v }),
   setSolveResult: (r) => set({ solveResult: r }),
-  setSolveTime: (t) => set({ solveTime: t }),
   setActiveTab: (tab) => set({ activeTab: tab }),
+  loadSettings: async () => {
+    const settings = await api.getSettings();
+    set({ settings });
+  },
   updateSettings: (partial) =>
-    set((state) => ({
-      settings: { ...state.settings, ...partial },
-    })),
-  resetSettings: () => set({ settings: { ...defaultSettings } }),
+    set((state) => {
+      const settings = { ...state.settings, ...partial };
+      api.updateSettings(settings).then((stored) => set({ settings: stored }));
+      return { settings };
+    }),
+  resetSettings: () => {
+    set({ settings: { ...defaultSettings } });
+    api.resetSettings().then((stored) => set({ settings: stored }));
+  },
   loadPuzzle: (width, height, rowHints, colHints) =>
     set({
       width,
@@ -125,6 +132,5 @@ export const usePuzzleStore = create<PuzzleState>((set) => ({
       colHints,
       grid: createEmptyGrid(width, height),
       solveResult: null,
-      solveTime: null,
     }),
 }));
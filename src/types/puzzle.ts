@@ -10,6 +10,7 @@ export interface PuzzleData {
   rowHints: number[][];
   colHints: number[][];
   metadata?: PuzzleMetadata;
+  solution?: string;
 }
 
 export interface PuzzleMetadata {
@@ -19,21 +20,230 @@ export interface PuzzleMetadata {
   createdAt?: string;
 }
 
-export type SolveResult =
+export interface SolveStats {
+  elapsedSeconds: number;
+  nodes: number;
+  maxDepth: number;
+  lineSolverCalls: number;
+  cellsByLogic: number;
+  cellsBySearch: number;
+}
+
+/** Deterministic cap on search work, alongside the timeout. */
+export interface SearchBudget {
+  maxNodes?: number;
+  maxLineSolverCalls?: number;
+}
+
+/** How a solve should run; missing fields take the solver's defaults. */
+export interface SolverOptions {
+  maxSolutions?: number;
+  timeoutSeconds?: number;
+  budget?: SearchBudget;
+}
+
+export type SolveResult = { stats: SolveStats } & (
   | { type: "UniqueSolution"; grid: number[][] }
   | { type: "MultipleSolutions"; grids: number[][][] }
   | { type: "NoSolution" }
-  | { type: "Timeout"; elapsed_seconds: number };
+  | { type: "Timeout" }
+  | { type: "BudgetExhausted" }
+  | { type: "InvalidInput"; errors: ValidationError[]; messages: string[] }
+);
+
+export type SolutionCheck = {
+  type: "NoIntendedSolution" | "Matches" | "Differs" | "Undetermined";
+};
+
+export type Transform =
+  | "transpose"
+  | "antiTranspose"
+  | "rotate90"
+  | "rotate180"
+  | "rotate270"
+  | "flipHorizontal"
+  | "flipVertical";
+
+export type Axis = "Row" | "Column";
+
+export type ValidationError =
+  | {
+      type: "DimensionOutOfRange";
+      dimension: "Width" | "Height";
+      value: number;
+      min: number;
+      max: number;
+    }
+  | { type: "HintCountMismatch"; axis: Axis; count: number; expected: number }
+  | {
+      type: "HintTooLong";
+      axis: Axis;
+      index: number;
+      needed: number;
+      available: number;
+    }
+  | { type: "ZeroInMultiHint"; axis: Axis; index: number; position: number }
+  | { type: "SumMismatch"; row_total: number; col_total: number }
+  | {
+      type: "FillCountUnreachable";
+      axis: Axis;
+      index: number;
+      needed: number;
+      possible: number;
+    }
+  | {
+      type: "FillCountExceeded";
+      axis: Axis;
+      index: number;
+      needed: number;
+      forced: number;
+    }
+  | { type: "LineContradiction"; axis: Axis; index: number };
 
 export interface ValidationResult {
   valid: boolean;
-  errors: string[];
+  errors: ValidationError[];
+  messages: string[];
 }
 
+export type Locale = "ja" | "en";
+
 export interface AppSettings {
+  version: number;
   timeoutSeconds: number;
   maxGridSize: number;
   showSizeWarning: boolean;
   gridLineThickness: GridLineThickness;
   cellSize: CellSizeOption;
+  locale: Locale;
+}
+
+export type SolveStatus = "unsolved" | "inProgress" | "solved";
+
+export interface LibraryEntry {
+  id: string;
+  title?: string;
+  author?: string;
+  width: number;
+  height: number;
+  difficulty?: string;
+  status: SolveStatus;
+  tags: string[];
+  fingerprint: Fingerprint;
+}
+
+export interface Fingerprint {
+  exact: string;
+  symmetric: string;
+}
+
+export type DuplicateKind = "exact" | "symmetric";
+
+export interface Duplicate {
+  id: string;
+  title?: string;
+  kind: DuplicateKind;
+}
+
+export interface AddedPuzzle {
+  entry: LibraryEntry;
+  duplicates: Duplicate[];
+}
+
+export interface ImportReport {
+  added: AddedPuzzle[];
+  skipped: { path: string; duplicates: Duplicate[] }[];
+  failed: { path: string; message: string }[];
+}
+
+export type LibrarySortKey = "added" | "title" | "size" | "difficulty" | "status";
+
+export interface LibraryQuery {
+  text?: string;
+  difficulty?: string;
+  status?: SolveStatus;
+  tags?: string[];
+  minSize?: number;
+  maxSize?: number;
+  sort?: LibrarySortKey;
+  descending?: boolean;
+  page?: number;
+  pageSize?: number;
+}
+
+export interface LibraryPage {
+  entries: LibraryEntry[];
+  total: number;
+  page: number;
+  pageSize: number;
+}
+
+export type PlayerCell = "Unknown" | "Filled" | "Empty";
+
+export interface CellChange {
+  row: number;
+  col: number;
+  before: PlayerCell;
+  after: PlayerCell;
+}
+
+export interface Move {
+  changes: CellChange[];
+}
+
+export interface SavedGame {
+  version: number;
+  puzzle: PuzzleData;
+  libraryId?: string;
+  grid: PlayerCell[][];
+  elapsedSeconds: number;
+  history: Move[];
+  savedAt: number;
+}
+
+export interface GameSummary {
+  id: string;
+  title?: string;
+  width: number;
+  height: number;
+  libraryId?: string;
+  elapsedSeconds: number;
+  savedAt: number;
+  autosave: boolean;
+}
+
+export interface CellPosition {
+  row: number;
+  col: number;
+}
+
+export type MoveCheck =
+  | {
+      type: "Unique";
+      wrong_cells: CellPosition[];
+      rows_done: boolean[];
+      cols_done: boolean[];
+      solved: boolean;
+    }
+  | {
+      type: "Ambiguous";
+      consistent: boolean;
+      rows_done: boolean[];
+      cols_done: boolean[];
+      solved: boolean;
+    }
+  | { type: "NoSolution" }
+  | { type: "Timeout" };
+
+export type SolveKind = "auto" | "play";
+
+export type StatsGroupBy = "size" | "difficulty";
+
+export interface StatsGroup {
+  key: string;
+  kind: SolveKind;
+  count: number;
+  successes: number;
+  averageMs?: number;
+  totalNodes: number;
 }